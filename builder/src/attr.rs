@@ -0,0 +1,527 @@
+use proc_macro2::{Span, TokenStream};
+use quote::quote;
+use syn::meta::ParseNestedMeta;
+use syn::parse::Parse;
+use syn::spanned::Spanned;
+use syn::{
+    parenthesized, token, Attribute, Error, Expr, Ident, LitBool, LitStr, Meta, Path, Result,
+    Token, Visibility,
+};
+
+const STRUCT_EXPECTED: &str = "expected `builder(typestate)`, `builder(default)`, \
+     `builder(build_fn(...))`, `builder(setter(...))`, `builder(pattern = \"...\")`, \
+     `builder(serde)`, `builder(serde(...))`, `builder(env_prefix = \"...\")`, \
+     `builder(env_separator = \"...\")`, `builder(field_names(...))`, `builder(positional)`, \
+     `builder(name = \"...\")`, `builder(vis = \"...\")`, `builder(derive(...))`, \
+     `builder(no_std)`, `builder(cli)` or `builder(const)`";
+const BUILD_FN_EXPECTED: &str = "expected `build_fn(validate = \"...\")`, \
+     `build_fn(name = \"...\")`, `build_fn(error = \"...\")`, `build_fn(private)` or \
+     `build_fn(post_build = \"...\")`";
+const STRUCT_SETTER_EXPECTED: &str = "expected `setter(into)` or `setter(strip_option)`";
+const FIELD_EXPECTED: &str = "expected `builder(each = \"...\")`, `builder(default)`, \
+     `builder(default = \"...\")`, `builder(setter(...))`, `builder(sub_builder)`, \
+     `builder(merge = \"...\")`, `builder(serde(...))`, `builder(env = \"...\")`, \
+     `builder(env(skip))`, `builder(env_separator = \"...\")`, `builder(cli(skip))`, \
+     `builder(try_setter)`, `builder(field(...))`, `builder(default_with = \"...\")`, \
+     `builder(computed = \"...\")` or `builder(flatten)`";
+const TRY_SETTER_EXPECTED: &str = "expected `try_setter(deferred)`";
+const ENV_EXPECTED: &str = "expected `env(skip)`";
+const CLI_EXPECTED: &str = "expected `cli(skip)`";
+const FIELD_FIELD_EXPECTED: &str = "expected `field(vis = \"...\")`";
+const VARIANT_EXPECTED: &str =
+    "expected `builder(skip)`, `builder(field_names(...))` or `builder(unit)`";
+const FIELD_SETTER_EXPECTED: &str = "expected `setter(into)`, `setter(strip_option)`, \
+     `setter(name = \"...\")` or `setter(skip)`";
+
+/// Options parsed from the `#[builder(...)]` attributes on the struct itself.
+#[derive(Default)]
+pub struct StructAttrs {
+    pub typestate: bool,
+    /// Unset fields are taken from the struct's own `Default` impl.
+    pub default: bool,
+    pub build_fn: BuildFnAttrs,
+    /// Defaults for every field's `setter(...)` options.
+    pub setter: SetterAttrs,
+    /// `pattern = "..."`, with the span of the string for error reporting.
+    pub pattern: Option<(Pattern, Span)>,
+    /// The builder derives `serde::Deserialize`.
+    pub serde: bool,
+    /// `serde(...)` attributes to put on the builder struct.
+    pub serde_attrs: Vec<TokenStream>,
+    /// Every field is read by `from_env()` from the variable named by this
+    /// prefix followed by the field name in upper case.
+    pub env_prefix: Option<String>,
+    /// Default for every field's `env_separator`.
+    pub env_separator: Option<String>,
+    /// Names for the fields of a tuple struct, with the span of the list.
+    pub field_names: Option<(Vec<Ident>, Span)>,
+    /// Required fields are passed to a `new()` constructor rather than set
+    /// through setters. Holds the span of the key.
+    pub positional: Option<Span>,
+    /// Name of the builder struct, instead of `<Name>Builder`.
+    pub name: Option<Ident>,
+    /// Visibility of the builder and its methods, instead of the struct's.
+    pub vis: Option<Visibility>,
+    /// Traits the builder struct derives.
+    pub derives: Vec<Path>,
+    /// The generated code uses `core` and `alloc` only, not `std`.
+    pub no_std: bool,
+    /// The builder can be filled from command-line flags with
+    /// `parse_args()`.
+    pub cli: bool,
+    /// `builder()`, the setters and `build()` are `const fn`. Holds the span
+    /// of the key.
+    pub constant: Option<Span>,
+}
+
+/// How setters and `build()` take the builder.
+#[derive(Clone, Copy, PartialEq)]
+pub enum Pattern {
+    /// `&mut self -> &mut Self`; `build(&self)` clones every field.
+    Mutable,
+    /// `self -> Self`; `build(self)` moves every field.
+    Owned,
+    /// `&self -> Self`, returning a modified copy; `build(&self)` clones.
+    Immutable,
+}
+
+/// `#[builder(build_fn(...))]`: options for the generated `build()`.
+#[derive(Default)]
+pub struct BuildFnAttrs {
+    /// Called with the builder once every required field is known to be
+    /// set; an `Err` aborts the build.
+    pub validate: Option<Path>,
+    /// Name of the function, instead of `build`.
+    pub name: Option<Ident>,
+    /// The error type the function returns instead of the builder's own.
+    /// A missing field is converted to it with `From` from the generated
+    /// `<Builder>UninitializedFieldError`, any other error of the builder
+    /// from the builder's error.
+    pub error: Option<Path>,
+    /// The function is private to the module, e.g. to wrap it in a public
+    /// constructor of its own.
+    pub private: bool,
+    /// Called with a mutable reference to the built value before it is
+    /// returned.
+    pub post_build: Option<Path>,
+}
+
+impl StructAttrs {
+    pub fn parse(attrs: &[Attribute]) -> Result<Self> {
+        let mut out = StructAttrs::default();
+        for attr in builder_attrs(attrs) {
+            parse_list(attr, STRUCT_EXPECTED, |meta| {
+                if meta.path.is_ident("typestate") {
+                    out.typestate = true;
+                    Ok(true)
+                } else if meta.path.is_ident("default") {
+                    out.default = true;
+                    Ok(true)
+                } else if meta.path.is_ident("build_fn") {
+                    meta.parse_nested_meta(|meta| {
+                        if meta.path.is_ident("validate") {
+                            out.build_fn.validate = Some(lit_value(&meta)?);
+                            Ok(())
+                        } else if meta.path.is_ident("name") {
+                            out.build_fn.name = Some(lit_value(&meta)?);
+                            Ok(())
+                        } else if meta.path.is_ident("error") {
+                            out.build_fn.error = Some(lit_value(&meta)?);
+                            Ok(())
+                        } else if meta.path.is_ident("private") {
+                            out.build_fn.private = true;
+                            Ok(())
+                        } else if meta.path.is_ident("post_build") {
+                            out.build_fn.post_build = Some(lit_value(&meta)?);
+                            Ok(())
+                        } else {
+                            Err(Error::new_spanned(&meta.path, BUILD_FN_EXPECTED))
+                        }
+                    })?;
+                    Ok(true)
+                } else if meta.path.is_ident("setter") {
+                    out.setter.parse(meta, false)?;
+                    Ok(true)
+                } else if meta.path.is_ident("pattern") {
+                    let lit: LitStr = meta.value()?.parse()?;
+                    let pattern = match lit.value().as_str() {
+                        "mutable" => Pattern::Mutable,
+                        "owned" => Pattern::Owned,
+                        "immutable" => Pattern::Immutable,
+                        _ => {
+                            return Err(Error::new_spanned(
+                                lit,
+                                "expected `\"mutable\"`, `\"owned\"` or `\"immutable\"`",
+                            ))
+                        }
+                    };
+                    out.pattern = Some((pattern, lit.span()));
+                    Ok(true)
+                } else if meta.path.is_ident("serde") {
+                    out.serde = true;
+                    out.serde_attrs.extend(serde_attr(meta)?);
+                    Ok(true)
+                } else if meta.path.is_ident("env_prefix") {
+                    let lit: LitStr = meta.value()?.parse()?;
+                    out.env_prefix = Some(lit.value());
+                    Ok(true)
+                } else if meta.path.is_ident("env_separator") {
+                    out.env_separator = Some(separator_value(meta)?);
+                    Ok(true)
+                } else if meta.path.is_ident("field_names") {
+                    out.field_names = Some(field_names_value(meta)?);
+                    Ok(true)
+                } else if meta.path.is_ident("positional") {
+                    out.positional = Some(meta.path.span());
+                    Ok(true)
+                } else if meta.path.is_ident("name") {
+                    out.name = Some(lit_value(meta)?);
+                    Ok(true)
+                } else if meta.path.is_ident("vis") {
+                    out.vis = Some(lit_value(meta)?);
+                    Ok(true)
+                } else if meta.path.is_ident("derive") {
+                    let content;
+                    parenthesized!(content in meta.input);
+                    let paths = content.parse_terminated(Path::parse_mod_style, Token![,])?;
+                    out.derives.extend(paths);
+                    Ok(true)
+                } else if meta.path.is_ident("no_std") {
+                    out.no_std = true;
+                    Ok(true)
+                } else if meta.path.is_ident("cli") {
+                    out.cli = true;
+                    Ok(true)
+                } else if meta.path.is_ident("const") {
+                    out.constant = Some(meta.path.span());
+                    Ok(true)
+                } else {
+                    Ok(false)
+                }
+            })?;
+        }
+        Ok(out)
+    }
+}
+
+/// Options parsed from the `#[builder(...)]` attributes on a single field.
+#[derive(Default)]
+pub struct FieldAttrs {
+    pub each: Option<Ident>,
+    pub default: Option<FieldDefault>,
+    pub setter: SetterAttrs,
+    /// The field's type derives `Builder` too, and is configured through its
+    /// own builder rather than set as a whole. The nested builder must be
+    /// reachable by the same path as the type, and must be consumed by
+    /// `build()` only if this one is. Holds the span of the key.
+    pub sub_builder: Option<Span>,
+    /// Like `sub_builder`, but the nested builder's setters are forwarded
+    /// from the outer builder. Holds the span of the key.
+    pub flatten: Option<Span>,
+    /// `merge = "..."`, with the span of the string for error reporting.
+    pub merge: Option<(Merge, Span)>,
+    /// `serde(...)` attributes to put on the builder's field.
+    pub serde_attrs: Vec<TokenStream>,
+    /// `env = "..."`, with the span of the string for error reporting.
+    pub env: Option<(String, Span)>,
+    /// `env(skip)`: left out of `from_env()` despite the struct's
+    /// `env_prefix`. Holds the span of the key.
+    pub env_skip: Option<Span>,
+    /// `cli(skip)`: no flag in `parse_args()`.
+    pub cli_skip: bool,
+    /// What `from_env()` splits a collection's variable on.
+    pub env_separator: Option<String>,
+    /// `try_setter` or `try_setter(deferred)`, with the span of the key.
+    pub try_setter: Option<(TrySetter, Span)>,
+    /// `field(vis = "...")`: visibility of the builder's field.
+    pub vis: Option<Visibility>,
+    /// The field is not part of the builder, and `build()` sets it to this
+    /// expression instead.
+    pub computed: Option<Expr>,
+    /// Span of the first option other than `computed`, which cannot be
+    /// combined with it.
+    pub other: Option<Span>,
+}
+
+/// How `merge()` combines a collection set in both builders.
+#[derive(Clone, Copy, PartialEq)]
+pub enum Merge {
+    /// The other builder's collection replaces this one's.
+    Replace,
+    /// The other builder's items are added after this one's.
+    Append,
+}
+
+/// Where a `try_` setter reports a value that fails to convert.
+#[derive(Clone, Copy, PartialEq)]
+pub enum TrySetter {
+    /// The setter returns the conversion error.
+    Immediate,
+    /// The setter records the error in the builder, and `build()` returns it.
+    Deferred,
+}
+
+/// `#[builder(setter(...))]`. Only `into` and `strip_option` may be given on
+/// the struct, where they set the default for every field.
+#[derive(Default)]
+pub struct SetterAttrs {
+    /// Setters accept any `impl Into<T>`.
+    pub into: Option<bool>,
+    /// Setters for `Option<T>` fields accept `T` rather than `Option<T>`.
+    pub strip_option: Option<bool>,
+    pub name: Option<Ident>,
+    /// No setter at all; the field must get its value from a default.
+    pub skip: bool,
+}
+
+impl SetterAttrs {
+    fn parse(&mut self, meta: &ParseNestedMeta, on_field: bool) -> Result<()> {
+        meta.parse_nested_meta(|meta| {
+            if meta.path.is_ident("into") {
+                self.into = Some(flag_value(&meta)?);
+            } else if meta.path.is_ident("strip_option") {
+                self.strip_option = Some(flag_value(&meta)?);
+            } else if on_field && meta.path.is_ident("name") {
+                self.name = Some(lit_value(&meta)?);
+            } else if on_field && meta.path.is_ident("skip") {
+                self.skip = true;
+            } else if on_field {
+                return Err(Error::new_spanned(&meta.path, FIELD_SETTER_EXPECTED));
+            } else {
+                return Err(Error::new_spanned(&meta.path, STRUCT_SETTER_EXPECTED));
+            }
+            Ok(())
+        })
+    }
+}
+
+/// `#[builder(default)]`, `#[builder(default = "expr")]` or
+/// `#[builder(default_with = "closure")]`.
+pub enum FieldDefault {
+    Trait,
+    Expr(Expr),
+    With(Expr),
+}
+
+impl FieldAttrs {
+    pub fn parse(attrs: &[Attribute]) -> Result<Self> {
+        let mut out = FieldAttrs::default();
+        for attr in builder_attrs(attrs) {
+            parse_list(attr, FIELD_EXPECTED, |meta| {
+                if !meta.path.is_ident("computed") && out.other.is_none() {
+                    out.other = Some(meta.path.span());
+                }
+                if meta.path.is_ident("each") {
+                    out.each = Some(lit_value(meta)?);
+                    Ok(true)
+                } else if meta.path.is_ident("default") {
+                    out.default = Some(if meta.input.peek(Token![=]) {
+                        FieldDefault::Expr(lit_value(meta)?)
+                    } else {
+                        FieldDefault::Trait
+                    });
+                    Ok(true)
+                } else if meta.path.is_ident("default_with") {
+                    out.default = Some(FieldDefault::With(lit_value(meta)?));
+                    Ok(true)
+                } else if meta.path.is_ident("computed") {
+                    out.computed = Some(lit_value(meta)?);
+                    Ok(true)
+                } else if meta.path.is_ident("setter") {
+                    out.setter.parse(meta, true)?;
+                    Ok(true)
+                } else if meta.path.is_ident("sub_builder") {
+                    out.sub_builder = Some(meta.path.span());
+                    Ok(true)
+                } else if meta.path.is_ident("flatten") {
+                    out.flatten = Some(meta.path.span());
+                    Ok(true)
+                } else if meta.path.is_ident("merge") {
+                    let lit: LitStr = meta.value()?.parse()?;
+                    let merge = match lit.value().as_str() {
+                        "replace" => Merge::Replace,
+                        "append" => Merge::Append,
+                        _ => {
+                            return Err(Error::new_spanned(
+                                lit,
+                                "expected `\"replace\"` or `\"append\"`",
+                            ))
+                        }
+                    };
+                    out.merge = Some((merge, lit.span()));
+                    Ok(true)
+                } else if meta.path.is_ident("serde") && meta.input.peek(token::Paren) {
+                    out.serde_attrs.extend(serde_attr(meta)?);
+                    Ok(true)
+                } else if meta.path.is_ident("env") && meta.input.peek(token::Paren) {
+                    skip_value(meta, ENV_EXPECTED)?;
+                    out.env_skip = Some(meta.path.span());
+                    Ok(true)
+                } else if meta.path.is_ident("env") {
+                    let lit: LitStr = meta.value()?.parse()?;
+                    out.env = Some((lit.value(), lit.span()));
+                    Ok(true)
+                } else if meta.path.is_ident("env_separator") {
+                    out.env_separator = Some(separator_value(meta)?);
+                    Ok(true)
+                } else if meta.path.is_ident("cli") {
+                    skip_value(meta, CLI_EXPECTED)?;
+                    out.cli_skip = true;
+                    Ok(true)
+                } else if meta.path.is_ident("try_setter") {
+                    let mut mode = TrySetter::Immediate;
+                    if meta.input.peek(token::Paren) {
+                        meta.parse_nested_meta(|meta| {
+                            if meta.path.is_ident("deferred") {
+                                mode = TrySetter::Deferred;
+                                Ok(())
+                            } else {
+                                Err(Error::new_spanned(&meta.path, TRY_SETTER_EXPECTED))
+                            }
+                        })?;
+                    }
+                    out.try_setter = Some((mode, meta.path.span()));
+                    Ok(true)
+                } else if meta.path.is_ident("field") {
+                    meta.parse_nested_meta(|meta| {
+                        if meta.path.is_ident("vis") {
+                            out.vis = Some(lit_value(&meta)?);
+                            Ok(())
+                        } else {
+                            Err(Error::new_spanned(&meta.path, FIELD_FIELD_EXPECTED))
+                        }
+                    })?;
+                    Ok(true)
+                } else {
+                    Ok(false)
+                }
+            })?;
+        }
+        Ok(out)
+    }
+}
+
+/// Options parsed from the `#[builder(...)]` attributes on an enum variant.
+#[derive(Default)]
+pub struct VariantAttrs {
+    /// No builder is generated for the variant.
+    pub skip: bool,
+    /// Names for the fields of a tuple variant, which opt it in to having
+    /// a builder, with the span of the list.
+    pub field_names: Option<(Vec<Ident>, Span)>,
+    /// A unit variant opts in to having a builder with no setters.
+    pub unit: bool,
+}
+
+impl VariantAttrs {
+    pub fn parse(attrs: &[Attribute]) -> Result<Self> {
+        let mut out = VariantAttrs::default();
+        for attr in builder_attrs(attrs) {
+            parse_list(attr, VARIANT_EXPECTED, |meta| {
+                if meta.path.is_ident("skip") {
+                    out.skip = true;
+                    Ok(true)
+                } else if meta.path.is_ident("field_names") {
+                    out.field_names = Some(field_names_value(meta)?);
+                    Ok(true)
+                } else if meta.path.is_ident("unit") {
+                    out.unit = true;
+                    Ok(true)
+                } else {
+                    Ok(false)
+                }
+            })?;
+        }
+        Ok(out)
+    }
+}
+
+fn builder_attrs(attrs: &[Attribute]) -> impl Iterator<Item = &Attribute> {
+    attrs.iter().filter(|attr| attr.path().is_ident("builder"))
+}
+
+/// Walks the nested metas of one `#[builder(...)]` attribute. The callback
+/// returns `Ok(false)` for keys it does not recognize, in which case the whole
+/// attribute is reported with the `expected` message.
+fn parse_list(
+    attr: &Attribute,
+    expected: &str,
+    mut f: impl FnMut(&ParseNestedMeta) -> Result<bool>,
+) -> Result<()> {
+    let unrecognized = || Error::new_spanned(&attr.meta, expected);
+    if !matches!(attr.meta, Meta::List(_)) {
+        return Err(unrecognized());
+    }
+    attr.parse_nested_meta(|meta| {
+        if f(&meta)? {
+            Ok(())
+        } else {
+            Err(unrecognized())
+        }
+    })
+}
+
+/// Parses `key = "..."`, where the string holds Rust syntax such as an
+/// identifier, path or expression.
+fn lit_value<T: Parse>(meta: &ParseNestedMeta) -> Result<T> {
+    let lit: LitStr = meta.value()?.parse()?;
+    lit.parse()
+}
+
+/// Takes `serde(...)` as written, to be passed through as an attribute of
+/// the builder, or nothing for a bare `serde`.
+fn serde_attr(meta: &ParseNestedMeta) -> Result<Option<TokenStream>> {
+    if !cfg!(feature = "serde") {
+        return Err(Error::new_spanned(
+            &meta.path,
+            "`builder(serde)` requires the `serde` feature of `derive_builder`",
+        ));
+    }
+    if !meta.input.peek(token::Paren) {
+        return Ok(None);
+    }
+    let path = &meta.path;
+    let content;
+    parenthesized!(content in meta.input);
+    let args: TokenStream = content.parse()?;
+    Ok(Some(quote!(#path(#args))))
+}
+
+/// Parses `field_names("a", "b")`, with the span of the list.
+fn field_names_value(meta: &ParseNestedMeta) -> Result<(Vec<Ident>, Span)> {
+    let content;
+    let parens = parenthesized!(content in meta.input);
+    let names = content.parse_terminated(<LitStr as Parse>::parse, Token![,])?;
+    let names = names.iter().map(LitStr::parse).collect::<Result<_>>()?;
+    Ok((names, parens.span.join()))
+}
+
+/// Parses the `(skip)` of a key such as `env(skip)`.
+fn skip_value(meta: &ParseNestedMeta, expected: &str) -> Result<()> {
+    meta.parse_nested_meta(|meta| {
+        if meta.path.is_ident("skip") {
+            Ok(())
+        } else {
+            Err(Error::new_spanned(&meta.path, expected))
+        }
+    })
+}
+
+/// Parses `env_separator = "..."`, which must not be empty.
+fn separator_value(meta: &ParseNestedMeta) -> Result<String> {
+    let lit: LitStr = meta.value()?.parse()?;
+    if lit.value().is_empty() {
+        return Err(Error::new_spanned(lit, "the separator must not be empty"));
+    }
+    Ok(lit.value())
+}
+
+/// Parses either a bare `key` (meaning true) or `key = true/false`.
+fn flag_value(meta: &ParseNestedMeta) -> Result<bool> {
+    if meta.input.peek(Token![=]) {
+        let lit: LitBool = meta.value()?.parse()?;
+        Ok(lit.value)
+    } else {
+        Ok(true)
+    }
+}
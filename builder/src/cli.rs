@@ -0,0 +1,244 @@
+//! `#[builder(cli)]`: `parse_args()`, a builder with fields set from
+//! command-line flags named after their setters.
+
+use crate::expand::Context;
+use crate::field::{Field, Kind};
+use crate::parse;
+use crate::ty::{self, Item};
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::ext::IdentExt;
+use syn::{Expr, Ident, Lit, Meta, Type};
+
+/// A flag `parse_args()` accepts.
+struct Flag<'a> {
+    field: &'a Field,
+    /// The flag as given on the command line, e.g. `--current-dir`.
+    name: String,
+    /// The type the flag's value is parsed as with `FromStr`.
+    ty: Type,
+    /// The flag may be given more than once, adding an item each time.
+    repeated: bool,
+    /// A `bool` field, set to true by the flag alone.
+    switch: bool,
+}
+
+/// Whether the builder has `parse_args()`, which then needs error variants
+/// for the arguments it rejects.
+pub fn is_used(cx: &Context) -> bool {
+    cx.attrs.cli
+}
+
+/// Generates `parse_args()` and `HELP` for a builder with `builder(cli)`.
+pub fn expand(cx: &Context) -> Option<TokenStream> {
+    if !is_used(cx) {
+        return None;
+    }
+
+    let vis = &cx.vis;
+    let alloc = cx.alloc();
+    let builder_ident = &cx.builder_ident;
+    let error_ident = &cx.error_ident;
+    let (impl_generics, ty_generics, where_clause) = cx.generics.split_for_impl();
+    let empty = cx.builder_literal(|_| quote!(::core::option::Option::None));
+    let help = help(cx);
+
+    let arms = flags(cx).into_iter().map(|flag| {
+        let name = &flag.name;
+        let ident = &flag.field.ident;
+        let ty = &flag.ty;
+        // A switch takes no value unless it is given inline, as in
+        // `--verbose=false`.
+        let missing = if flag.switch {
+            quote!(#alloc::borrow::ToOwned::to_owned("true"))
+        } else {
+            quote! {
+                match ::core::iter::Iterator::next(&mut args) {
+                    ::core::option::Option::Some(value) => value,
+                    ::core::option::Option::None => {
+                        return ::core::result::Result::Err(#error_ident::MissingValue(
+                            #alloc::borrow::ToOwned::to_owned(#name),
+                        ));
+                    }
+                }
+            }
+        };
+        let store = if flag.repeated {
+            quote! {
+                ::core::iter::Extend::extend(
+                    builder.#ident.get_or_insert_with(::core::default::Default::default),
+                    ::core::iter::once(value),
+                );
+            }
+        } else if let Kind::Optional(_) = flag.field.kind {
+            quote! {
+                builder.#ident = ::core::option::Option::Some(::core::option::Option::Some(value));
+            }
+        } else {
+            quote! {
+                builder.#ident = ::core::option::Option::Some(value);
+            }
+        };
+        let parse = parse::parse(ty, quote!(&value));
+        let parse = quote! {
+            match #parse {
+                ::core::result::Result::Ok(value) => value,
+                ::core::result::Result::Err(err) => {
+                    return ::core::result::Result::Err(#error_ident::InvalidValue {
+                        flag: #alloc::borrow::ToOwned::to_owned(#name),
+                        message: #alloc::string::ToString::to_string(&err),
+                    });
+                }
+            }
+        };
+        quote! {
+            #name => {
+                let value = match inline {
+                    ::core::option::Option::Some(value) => #alloc::borrow::ToOwned::to_owned(value),
+                    ::core::option::Option::None => #missing,
+                };
+                let value = #parse;
+                #store
+            }
+        }
+    });
+
+    let helpers = parse::helpers(
+        "`{Self}` cannot be the value of a command-line flag, which `parse_args()` \
+         parses with `FromStr`",
+        "add `#[builder(cli(skip))]` to leave the field out of `parse_args()`",
+    );
+
+    Some(quote! {
+        impl #impl_generics #builder_ident #ty_generics #where_clause {
+            /// The text describing the flags `parse_args()` accepts.
+            #vis const HELP: &'static ::core::primitive::str = #help;
+
+            /// Returns a builder with fields set from flags named after
+            /// their setters, e.g. `--name value` or `--name=value`, parsed
+            /// with `FromStr`. `args` does not include the program name, so
+            /// `std::env::args().skip(1)` is typical. Collections get one item
+            /// per occurrence of their flag.
+            #vis fn parse_args<I>(args: I) -> ::core::result::Result<Self, #error_ident>
+            where
+                I: ::core::iter::IntoIterator<Item = #alloc::string::String>,
+            {
+                #helpers
+                let mut builder = #empty;
+                let mut args = ::core::iter::IntoIterator::into_iter(args);
+                while let ::core::option::Option::Some(arg) = ::core::iter::Iterator::next(&mut args) {
+                    let (flag, inline) = match arg.split_once('=') {
+                        ::core::option::Option::Some((flag, value)) if arg.starts_with("--") => {
+                            (flag, ::core::option::Option::Some(value))
+                        }
+                        _ => (arg.as_str(), ::core::option::Option::None),
+                    };
+                    match flag {
+                        "--help" => return ::core::result::Result::Err(#error_ident::Help),
+                        #(#arms)*
+                        _ => {
+                            return ::core::result::Result::Err(#error_ident::UnknownArgument(arg));
+                        }
+                    }
+                }
+                ::core::result::Result::Ok(builder)
+            }
+        }
+    })
+}
+
+/// The flags for the fields that have a setter taking a single value, which
+/// must implement `FromStr`. Others, such as maps and nested builders, and
+/// fields with `cli(skip)` are left to be set in code.
+fn flags<'a>(cx: &'a Context) -> Vec<Flag<'a>> {
+    let flag_name = |setter: &Ident| format!("--{}", setter.unraw().to_string().replace('_', "-"));
+    let is_bool = |ty: &Type| matches!(ty, Type::Path(path) if path.path.is_ident("bool"));
+    cx.fields
+        .iter()
+        .filter(|field| !field.setter.skip && !field.cli_skip)
+        .filter_map(|field| {
+            let (setter, ty, repeated) = match &field.kind {
+                // A collection without `each` still gets an item per flag.
+                Kind::Plain => match ty::collection_item(&field.ty) {
+                    Item::Single(item) => (&field.setter.name, *item, true),
+                    Item::Pair(..) => return None,
+                    Item::Unknown => (&field.setter.name, field.ty.clone(), false),
+                },
+                Kind::Optional(inner) => (&field.setter.name, inner.clone(), false),
+                Kind::Each {
+                    setter,
+                    item: Item::Single(item),
+                } => (setter, (**item).clone(), true),
+                Kind::Each { .. } | Kind::Sub(_) => return None,
+            };
+            Some(Flag {
+                field,
+                name: flag_name(setter),
+                switch: !repeated && is_bool(&ty),
+                ty,
+                repeated,
+            })
+        })
+        .collect()
+}
+
+/// One line per flag, with the first paragraph of the field's doc comment.
+pub fn help(cx: &Context) -> String {
+    let entries: Vec<(String, String)> = flags(cx)
+        .iter()
+        .map(|flag| {
+            let usage = match (flag.switch, flag.repeated) {
+                (true, _) => flag.name.clone(),
+                (false, false) => format!("{} <VALUE>", flag.name),
+                (false, true) => format!("{} <VALUE>...", flag.name),
+            };
+            let mut about = summary(flag.field);
+            if flag.field.is_required() {
+                about.push_str(if about.is_empty() {
+                    "(required)"
+                } else {
+                    " (required)"
+                });
+            }
+            (usage, about)
+        })
+        .chain(Some(("--help".to_owned(), "Prints this help.".to_owned())))
+        .collect();
+
+    let width = entries
+        .iter()
+        .map(|(usage, _)| usage.len())
+        .max()
+        .unwrap_or(0);
+    let mut help = String::from("Options:\n");
+    for (usage, about) in entries {
+        let line = format!("  {:width$}  {}", usage, about, width = width);
+        help.push_str(line.trim_end());
+        help.push('\n');
+    }
+    help
+}
+
+fn summary(field: &Field) -> String {
+    let mut lines = Vec::new();
+    for attr in &field.docs {
+        let Meta::NameValue(meta) = &attr.meta else {
+            continue;
+        };
+        let Expr::Lit(expr) = &meta.value else {
+            continue;
+        };
+        let Lit::Str(lit) = &expr.lit else {
+            continue;
+        };
+        let line = lit.value().trim().to_owned();
+        if line.is_empty() {
+            if lines.is_empty() {
+                continue;
+            }
+            break;
+        }
+        lines.push(line);
+    }
+    lines.join(" ")
+}
@@ -0,0 +1,83 @@
+//! `#[builder(const)]`: `builder()`, the setters and `build()` are `const fn`,
+//! so that a value can be built in a `const` or `static` item. A required
+//! field left unset makes `build()` panic, which in a constant is a compile
+//! error naming the field.
+
+use crate::attr::Pattern;
+use crate::expand::Context;
+use crate::field::{DefaultValue, Kind};
+use crate::{cli, env};
+use syn::{Error, Result};
+
+pub fn is_used(cx: &Context) -> bool {
+    cx.attrs.constant.is_some()
+}
+
+/// Rejects the options that need trait methods, closures or an error type,
+/// none of which a `const fn` can use.
+pub fn check(cx: &Context) -> Result<()> {
+    let Some(span) = cx.attrs.constant else {
+        return Ok(());
+    };
+    let unsupported =
+        |what: &str| format!("`builder({})` is not supported with `builder(const)`", what);
+
+    if cx.attrs.typestate {
+        return Err(Error::new(span, unsupported("typestate")));
+    }
+
+    // `Clone::clone` cannot be called in a `const fn`, so `build()` has to
+    // move the fields out of the builder.
+    if let Some((pattern, span)) = cx.attrs.pattern {
+        if pattern != Pattern::Owned {
+            return Err(Error::new(span, unsupported("pattern = \"...\"")));
+        }
+    }
+
+    // A const `build()` returns the value itself, so there is no error to
+    // report a failed validation or conversion with.
+    if let Some(validate) = &cx.attrs.build_fn.validate {
+        return Err(Error::new_spanned(
+            validate,
+            "`build_fn(validate = \"...\")` is not supported with `builder(const)`",
+        ));
+    }
+
+    if let Some(error) = &cx.attrs.build_fn.error {
+        return Err(Error::new_spanned(
+            error,
+            "`build_fn(error = \"...\")` is not supported with `builder(const)`",
+        ));
+    }
+
+    if cx.attrs.default {
+        return Err(Error::new(span, unsupported("default")));
+    }
+
+    if env::is_used(cx) {
+        return Err(Error::new(
+            span,
+            "`builder(env = \"...\")` and `builder(env_prefix = \"...\")` are not supported \
+             with `builder(const)`",
+        ));
+    }
+
+    if cli::is_used(cx) {
+        return Err(Error::new(span, unsupported("cli")));
+    }
+
+    for field in &cx.fields {
+        let what = match (&field.kind, &field.default) {
+            (Kind::Sub(sub), _) => sub.attr(),
+            (Kind::Each { .. }, _) => "each = \"...\"",
+            (_, Some(DefaultValue::Trait)) => "default",
+            (_, Some(DefaultValue::With(_))) => "default_with = \"...\"",
+            _ if field.try_setter.is_some() => "try_setter",
+            _ if field.setter.into => "setter(into)",
+            _ => continue,
+        };
+        return Err(Error::new_spanned(&field.ident, unsupported(what)));
+    }
+
+    Ok(())
+}
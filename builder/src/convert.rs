@@ -0,0 +1,84 @@
+//! Conversions from a built value back into a builder with every field set.
+
+use crate::expand::Context;
+use crate::field::{Field, Kind};
+use proc_macro2::TokenStream;
+use quote::quote;
+
+/// Generates `impl From<Struct> for #builder_ty` and `Struct::to_builder`.
+/// Fields for which `is_state` holds are stored as a typestate `(T,)` rather
+/// than an `Option`.
+pub fn expand(
+    cx: &Context,
+    builder_ty: &TokenStream,
+    is_state: impl Fn(&Field) -> bool,
+) -> TokenStream {
+    let vis = &cx.vis;
+    let ident = cx.ident;
+    let builder_ident = &cx.builder_ident;
+    let (impl_generics, ty_generics, where_clause) = cx.generics.split_for_impl();
+
+    // The builder literal with each field set from `source.member`, or a
+    // clone of it.
+    let literal = |source: TokenStream, clone: bool| {
+        cx.builder_literal(|field| {
+            let member = &field.member;
+            let value = if clone {
+                quote!(::core::clone::Clone::clone(&#source.#member))
+            } else {
+                quote!(#source.#member)
+            };
+            match &field.kind {
+                _ if is_state(field) => quote!((#value,)),
+                Kind::Sub(sub) if sub.optional => quote! {
+                    ::core::option::Option::map(#value, ::core::convert::From::from)
+                },
+                Kind::Sub(_) => quote! {
+                    ::core::option::Option::Some(::core::convert::From::from(#value))
+                },
+                _ => quote!(::core::option::Option::Some(#value)),
+            }
+        })
+    };
+    let from_value = literal(quote!(value), false);
+    let from_self = literal(quote!(self), true);
+
+    // The bounds are higher-ranked so that they are only checked where
+    // `to_builder` is called, rather than rejecting the whole impl for a
+    // struct with a field that is not `Clone`.
+    let clone_bounds = cx.fields.iter().map(|field| {
+        let ty = &field.ty;
+        quote!(for<'__a> #ty: ::core::clone::Clone)
+    });
+
+    let from_doc = format!(
+        "A builder with every field set to its value in the [`{}`].",
+        ident,
+    );
+    let to_builder_doc = format!(
+        "Returns a [`{}`] with every field set to a clone of its value in \
+         `self`, for building a modified copy.",
+        builder_ident,
+    );
+
+    quote! {
+        impl #impl_generics ::core::convert::From<#ident #ty_generics> for #builder_ty
+        #where_clause
+        {
+            #[doc = #from_doc]
+            fn from(value: #ident #ty_generics) -> Self {
+                #from_value
+            }
+        }
+
+        impl #impl_generics #ident #ty_generics #where_clause {
+            #[doc = #to_builder_doc]
+            #vis fn to_builder(&self) -> #builder_ty
+            where
+                #(#clone_bounds,)*
+            {
+                #from_self
+            }
+        }
+    }
+}
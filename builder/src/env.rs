@@ -0,0 +1,146 @@
+//! `from_env()`: a builder with fields read from environment variables.
+
+use crate::expand::Context;
+use crate::field::{Field, Kind};
+use crate::parse;
+use crate::ty::{self, Item};
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{Error, Result, Type};
+
+/// Generates `from_env()` if any field is read from an environment variable.
+pub fn expand(cx: &Context) -> Result<Option<TokenStream>> {
+    let mut reads = Vec::new();
+    for field in &cx.fields {
+        if let Some(read) = read(cx, field)? {
+            reads.push(read);
+        }
+    }
+    if reads.is_empty() {
+        return Ok(None);
+    }
+    if cx.attrs.no_std {
+        return Err(Error::new_spanned(
+            cx.ident,
+            "`builder(env = \"...\")` and `builder(env_prefix = \"...\")` require `std`, \
+             and are not supported with `builder(no_std)`",
+        ));
+    }
+
+    let vis = &cx.vis;
+    let builder_ident = &cx.builder_ident;
+    let error_ident = &cx.error_ident;
+    let (impl_generics, ty_generics, where_clause) = cx.generics.split_for_impl();
+    let empty = cx.builder_literal(|_| quote!(::core::option::Option::None));
+    let helpers = parse::helpers(
+        "`{Self}` cannot be read from an environment variable, which `from_env()` \
+         parses with `FromStr`",
+        "add `#[builder(env(skip))]` to leave the field out of `from_env()`",
+    );
+
+    Ok(Some(quote! {
+        impl #impl_generics #builder_ident #ty_generics #where_clause {
+            /// Returns a builder with the fields that have an environment
+            /// variable set from it, parsed with `FromStr`. Fields whose
+            /// variable is not set are left unset.
+            #vis fn from_env() -> ::core::result::Result<Self, #error_ident> {
+                #helpers
+                let mut builder = #empty;
+                #(#reads)*
+                ::core::result::Result::Ok(builder)
+            }
+        }
+    }))
+}
+
+/// Whether any field is read by `from_env()`, which then needs an error
+/// variant to report the variables it could not parse.
+pub fn is_used(cx: &Context) -> bool {
+    cx.fields.iter().any(|field| field.env.is_some())
+}
+
+/// Sets `field` from its variable in `from_env()`. Maps and collections
+/// without a known item type are an error if their variable was named
+/// explicitly, and left out if it only came from the struct's `env_prefix`.
+/// Any other field is parsed with `FromStr`, and a type without it is an
+/// error at the field, which `env(skip)` leaves out of the prefix.
+fn read(cx: &Context, field: &Field) -> Result<Option<TokenStream>> {
+    let Some(env) = &field.env else {
+        return Ok(None);
+    };
+
+    let error_ident = &cx.error_ident;
+    let name = &env.name;
+    let separator = &env.separator;
+    let env_error = |message: TokenStream| {
+        quote! {
+            return ::core::result::Result::Err(#error_ident::Env {
+                var: ::std::borrow::ToOwned::to_owned(#name),
+                message: #message,
+            })
+        }
+    };
+    let parse = |ty: &Type, input: TokenStream| {
+        let error = env_error(quote!(::std::string::ToString::to_string(&err)));
+        let parse = parse::parse(ty, input);
+        quote! {
+            match #parse {
+                ::core::result::Result::Ok(value) => value,
+                ::core::result::Result::Err(err) => #error,
+            }
+        }
+    };
+    let ty = &field.ty;
+    let split = |item: &Type| {
+        let item = parse(item, quote!(item));
+        quote! {{
+            let mut items: #ty = ::core::default::Default::default();
+            for item in value.split(#separator) {
+                if !item.is_empty() {
+                    ::core::iter::Extend::extend(&mut items, ::core::iter::once(#item));
+                }
+            }
+            items
+        }}
+    };
+
+    let value = match &field.kind {
+        Kind::Sub(_) => {
+            Err("`builder(env = \"...\")` is not supported with `builder(sub_builder)`")
+        }
+        Kind::Optional(inner) => {
+            let inner = parse(inner, quote!(&value));
+            Ok(quote!(::core::option::Option::Some(#inner)))
+        }
+        Kind::Plain | Kind::Each { .. } => match ty::collection_item(ty) {
+            Item::Single(item) => Ok(split(&item)),
+            Item::Pair(..) => Err("`builder(env = \"...\")` is not supported for maps"),
+            Item::Unknown if matches!(field.kind, Kind::Plain) => Ok(parse(ty, quote!(&value))),
+            Item::Unknown => Err(
+                "`builder(env = \"...\")` requires a collection with a known \
+                 item type, such as `Vec<T>`",
+            ),
+        },
+    };
+    let value = match value {
+        Ok(value) => value,
+        Err(message) => {
+            return match env.span {
+                Some(span) => Err(Error::new(span, message)),
+                None => Ok(None),
+            }
+        }
+    };
+
+    let field_ident = &field.ident;
+    let not_unicode = env_error(quote!(::std::string::ToString::to_string(&err)));
+    Ok(Some(quote! {
+        match ::std::env::var(#name) {
+            ::core::result::Result::Ok(value) => {
+                builder.#field_ident = ::core::option::Option::Some(#value);
+            }
+            ::core::result::Result::Err(::std::env::VarError::NotPresent) => {}
+            ::core::result::Result::Err(err) => #not_unicode,
+        }
+    }))
+}
@@ -0,0 +1,269 @@
+use crate::expand::Context;
+use crate::field::Kind;
+use crate::{cli, env};
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::Ident;
+
+/// Generates `<Name>BuilderError`, one `Missing*` variant per required field,
+/// one variant per `sub_builder` or `flatten` field wrapping the nested
+/// builder's error, `Env` if `from_env()` is generated, `Conversion` if a
+/// `try_` setter defers its errors, the arguments rejected by `parse_args()`
+/// if it is generated, plus `Validation` for failures reported by user code.
+pub fn error_enum(cx: &Context) -> TokenStream {
+    let vis = &cx.vis;
+    let alloc = cx.alloc();
+    let error_ident = &cx.error_ident;
+    let doc = format!(
+        "Error returned by [`{}::{}`].",
+        cx.builder_ident,
+        cx.build_fn_name(),
+    );
+
+    let required: Vec<_> = cx.fields.iter().filter(|f| f.is_required()).collect();
+    let variants = required.iter().map(|f| f.missing_variant());
+    let variant_docs = required
+        .iter()
+        .map(|f| format!("The required field `{}` was not set.", f.name()));
+    let missing_display = required.iter().map(|f| {
+        let variant = f.missing_variant();
+        let message = format!("missing required field `{}`", f.name());
+        quote! {
+            #error_ident::#variant => f.write_str(#message),
+        }
+    });
+    let missing_paths = required.iter().map(|f| {
+        let variant = f.missing_variant();
+        let name = f.name();
+        quote! {
+            #error_ident::#variant => ::core::option::Option::Some(
+                #alloc::borrow::ToOwned::to_owned(#name),
+            ),
+        }
+    });
+
+    let nested: Vec<_> = cx
+        .fields
+        .iter()
+        .filter_map(|f| match &f.kind {
+            Kind::Sub(sub) => Some((f, sub)),
+            _ => None,
+        })
+        .collect();
+    let nested_variants = nested.iter().map(|(f, sub)| {
+        let variant = f.nested_variant();
+        let error = &sub.error;
+        let doc = format!("Building the nested `{}` failed.", f.name());
+        quote! {
+            #[doc = #doc]
+            #variant(#error),
+        }
+    });
+    // The fields of a flattened builder are reported as if they were this
+    // builder's own.
+    let nested_paths = nested.iter().map(|(f, sub)| {
+        let variant = f.nested_variant();
+        if sub.flatten.is_some() {
+            return quote!(#error_ident::#variant(err) => err.field_path(),);
+        }
+        let prefix = format!("{}.", f.name());
+        quote! {
+            #error_ident::#variant(err) => {
+                let path = err.field_path()?;
+                ::core::option::Option::Some(#alloc::format!("{}{}", #prefix, path))
+            }
+        }
+    });
+    let nested_display = nested.iter().map(|(f, sub)| {
+        let variant = f.nested_variant();
+        if sub.flatten.is_some() {
+            return quote!(#error_ident::#variant(err) => ::core::fmt::Display::fmt(err, f),);
+        }
+        let name = f.name();
+        quote! {
+            #error_ident::#variant(err) => match err.field_path() {
+                ::core::option::Option::Some(path) => {
+                    ::core::write!(f, "missing required field `{}.{}`", #name, path)
+                }
+                ::core::option::Option::None => ::core::write!(f, "`{}`: {}", #name, err),
+            },
+        }
+    });
+
+    let env = env::is_used(cx);
+    let env_variant = env.then(|| {
+        quote! {
+            /// An environment variable read by `from_env()` could not be
+            /// parsed.
+            Env {
+                var: #alloc::string::String,
+                message: #alloc::string::String,
+            },
+        }
+    });
+    let env_path = env.then(|| quote!(#error_ident::Env { .. } => ::core::option::Option::None,));
+    let env_display = env.then(|| {
+        quote! {
+            #error_ident::Env { var, message } => ::core::write!(
+                f,
+                "invalid value in environment variable `{}`: {}",
+                var,
+                message,
+            ),
+        }
+    });
+
+    let deferred = cx.defers_errors();
+    let conversion_variant = deferred.then(|| {
+        quote! {
+            /// A value passed to a `try_` setter could not be converted to
+            /// the field's type.
+            Conversion {
+                field: #alloc::string::String,
+                message: #alloc::string::String,
+            },
+        }
+    });
+    let conversion_path =
+        deferred.then(|| quote!(#error_ident::Conversion { .. } => ::core::option::Option::None,));
+    let conversion_display = deferred.then(|| {
+        quote! {
+            #error_ident::Conversion { field, message } => ::core::write!(
+                f,
+                "invalid value for field `{}`: {}",
+                field,
+                message,
+            ),
+        }
+    });
+
+    let cli = cli::is_used(cx);
+    let cli_variants = cli.then(|| {
+        quote! {
+            /// `parse_args()` was given `--help`. Displays the help text.
+            Help,
+            /// `parse_args()` was given an argument that is not a flag it
+            /// knows.
+            UnknownArgument(#alloc::string::String),
+            /// A flag given to `parse_args()` was not followed by a value.
+            MissingValue(#alloc::string::String),
+            /// The value of a flag given to `parse_args()` could not be
+            /// parsed.
+            InvalidValue {
+                flag: #alloc::string::String,
+                message: #alloc::string::String,
+            },
+        }
+    });
+    let cli_paths = cli.then(|| {
+        quote! {
+            #error_ident::Help
+            | #error_ident::UnknownArgument(_)
+            | #error_ident::MissingValue(_)
+            | #error_ident::InvalidValue { .. } => ::core::option::Option::None,
+        }
+    });
+    let cli_display = cli.then(|| {
+        let help = cli::help(cx);
+        quote! {
+            #error_ident::Help => f.write_str(#help),
+            #error_ident::UnknownArgument(arg) => {
+                ::core::write!(f, "unexpected argument `{}`; try `--help`", arg)
+            }
+            #error_ident::MissingValue(flag) => {
+                ::core::write!(f, "the flag `{}` requires a value", flag)
+            }
+            #error_ident::InvalidValue { flag, message } => {
+                ::core::write!(f, "invalid value for `{}`: {}", flag, message)
+            }
+        }
+    });
+
+    quote! {
+        #[doc = #doc]
+        #[derive(::core::fmt::Debug, ::core::clone::Clone, ::core::cmp::PartialEq, ::core::cmp::Eq)]
+        #vis enum #error_ident {
+            #(
+                #[doc = #variant_docs]
+                #variants,
+            )*
+            #(#nested_variants)*
+            #env_variant
+            #conversion_variant
+            #cli_variants
+            /// The builder's fields were rejected by a validation hook.
+            Validation(#alloc::string::String),
+        }
+
+        impl #error_ident {
+            /// The missing field, as a dotted path such as `tls.cert_path`
+            /// if it belongs to a nested builder, or `None` for errors other
+            /// than a missing field.
+            #vis fn field_path(&self) -> ::core::option::Option<#alloc::string::String> {
+                match self {
+                    #(#missing_paths)*
+                    #(#nested_paths)*
+                    #env_path
+                    #conversion_path
+                    #cli_paths
+                    #error_ident::Validation(_) => ::core::option::Option::None,
+                }
+            }
+        }
+
+        impl ::core::fmt::Display for #error_ident {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+                match self {
+                    #(#missing_display)*
+                    #(#nested_display)*
+                    #env_display
+                    #conversion_display
+                    #cli_display
+                    #error_ident::Validation(message) => f.write_str(message),
+                }
+            }
+        }
+
+        impl ::core::error::Error for #error_ident {}
+    }
+}
+
+/// The error a missing field is converted from into the error type given
+/// with `build_fn(error = "...")`.
+pub fn uninitialized_ident(cx: &Context) -> Ident {
+    format_ident!("{}UninitializedFieldError", cx.builder_ident)
+}
+
+/// Generates the `uninitialized_ident` struct, naming the missing field, if
+/// `build()` returns an error type of the user's choosing.
+pub fn uninitialized_struct(cx: &Context) -> Option<TokenStream> {
+    cx.attrs.build_fn.error.as_ref()?;
+    let vis = &cx.vis;
+    let ident = uninitialized_ident(cx);
+    let doc = format!(
+        "A required field that [`{}::{}`] found unset, converted with `From` into \
+         the error it returns.",
+        cx.builder_ident,
+        cx.build_fn_name(),
+    );
+    Some(quote! {
+        #[doc = #doc]
+        #[derive(::core::fmt::Debug, ::core::clone::Clone, ::core::cmp::PartialEq, ::core::cmp::Eq)]
+        #vis struct #ident(&'static str);
+
+        impl #ident {
+            /// The name of the field.
+            #vis fn field_name(&self) -> &'static str {
+                self.0
+            }
+        }
+
+        impl ::core::fmt::Display for #ident {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+                ::core::write!(f, "missing required field `{}`", self.0)
+            }
+        }
+
+        impl ::core::error::Error for #ident {}
+    })
+}
@@ -0,0 +1,1063 @@
+use crate::attr::{Pattern, StructAttrs, TrySetter, VariantAttrs};
+use crate::field::{snake_case, Computed, DefaultValue, Field, Kind, StructField};
+use crate::order::{self, Binding};
+use crate::setter::{self, Receiver, Setter};
+use crate::{cli, constant, convert, env, error, flatten, introspect, merge, ty, typestate};
+use proc_macro2::{Span, TokenStream};
+use quote::{format_ident, quote, quote_spanned, ToTokens};
+use syn::ext::IdentExt;
+use syn::spanned::Spanned;
+use syn::{
+    Data, DataStruct, DeriveInput, Error, Fields, GenericParam, Generics, Ident, Index, Member,
+    Result, Visibility,
+};
+
+/// Everything the code generators need to know about one builder: that of
+/// a struct, or of one variant of an enum.
+pub struct Context<'a> {
+    pub ident: &'a Ident,
+    /// The enum variant the builder builds, if any.
+    pub variant: Option<&'a Ident>,
+    /// Visibility of the builder and of everything generated for it.
+    pub vis: Visibility,
+    pub generics: &'a Generics,
+    pub builder_ident: Ident,
+    pub error_ident: Ident,
+    /// The associated function on the struct or enum returning the builder.
+    pub builder_fn: Ident,
+    pub attrs: StructAttrs,
+    /// The fields of the struct that the builder has a field for.
+    pub fields: Vec<Field>,
+    /// The fields of the struct that `build()` computes instead.
+    pub computed: Vec<Computed>,
+    /// Indices into `fields` followed by `computed`, as `order::sort`ed.
+    order: Vec<usize>,
+}
+
+impl<'a> Context<'a> {
+    fn new(
+        input: &'a DeriveInput,
+        variant: Option<(&'a Ident, VariantAttrs)>,
+        fields: &'a Fields,
+    ) -> Result<Self> {
+        let mut attrs = StructAttrs::parse(&input.attrs)?;
+        let (builder_ident, builder_fn) = match &variant {
+            Some((variant, variant_attrs)) => {
+                // Field names belong to a tuple variant, not to the enum.
+                if let Some((_, span)) = &attrs.field_names {
+                    return Err(Error::new(
+                        *span,
+                        "`builder(field_names(...))` goes on the tuple variants of an enum",
+                    ));
+                }
+                attrs.field_names = variant_attrs.field_names.clone();
+                // The enum's own `Default` says nothing about a variant's
+                // fields.
+                if attrs.default {
+                    return Err(Error::new_spanned(
+                        &input.ident,
+                        "`builder(default)` is not supported on enums; \
+                         put it on the fields of the variants instead",
+                    ));
+                }
+                // Every variant would get the same `new()`.
+                if let Some(span) = attrs.positional {
+                    return Err(Error::new(
+                        span,
+                        "`builder(positional)` is not supported on enums",
+                    ));
+                }
+                // Every variant needs a builder of its own.
+                if let Some(name) = &attrs.name {
+                    return Err(Error::new_spanned(
+                        name,
+                        "`builder(name = \"...\")` is not supported on enums",
+                    ));
+                }
+                (
+                    format_ident!("{}{}Builder", input.ident, variant),
+                    format_ident!("{}_builder", snake_case(&variant.unraw().to_string())),
+                )
+            }
+            None => (
+                attrs
+                    .name
+                    .clone()
+                    .unwrap_or_else(|| format_ident!("{}Builder", input.ident)),
+                format_ident!("builder"),
+            ),
+        };
+        let error_ident = format_ident!("{}Error", builder_ident);
+        let parsed: Vec<StructField> = match (fields, &attrs.field_names) {
+            (Fields::Named(fields), None) => fields
+                .named
+                .iter()
+                .map(|field| {
+                    let ident = field.ident.clone().expect("named field");
+                    Field::from_syn(field, Member::Named(ident.clone()), ident, &attrs)
+                })
+                .collect::<Result<_>>()?,
+            (Fields::Unnamed(fields), names) => {
+                if let Some((names, span)) = names {
+                    if names.len() != fields.unnamed.len() {
+                        return Err(Error::new(
+                            *span,
+                            format!(
+                                "expected {} field names, one for each field",
+                                fields.unnamed.len(),
+                            ),
+                        ));
+                    }
+                }
+                fields
+                    .unnamed
+                    .iter()
+                    .enumerate()
+                    .map(|(i, field)| {
+                        let ident = match names {
+                            Some((names, _)) => names[i].clone(),
+                            None => format_ident!("_{}", i),
+                        };
+                        let member = Member::Unnamed(Index {
+                            index: i as u32,
+                            span: field.ty.span(),
+                        });
+                        Field::from_syn(field, member, ident, &attrs)
+                    })
+                    .collect::<Result<_>>()?
+            }
+            (_, Some((_, span))) => {
+                return Err(Error::new(
+                    *span,
+                    "`builder(field_names(...))` requires a tuple struct or variant",
+                ))
+            }
+            // A unit variant with `builder(unit)`; unit structs are rejected
+            // by `derive`.
+            (Fields::Unit, None) => Vec::new(),
+        };
+
+        let mut fields = Vec::new();
+        let mut computed = Vec::new();
+        for field in parsed {
+            match field {
+                StructField::Builder(field) => fields.push(*field),
+                StructField::Computed(field) => computed.push(*field),
+            }
+        }
+        let order = order::sort(&fields, &computed)?;
+
+        Ok(Context {
+            ident: &input.ident,
+            variant: variant.map(|(variant, _)| variant),
+            vis: attrs.vis.clone().unwrap_or_else(|| input.vis.clone()),
+            generics: &input.generics,
+            builder_ident,
+            error_ident,
+            builder_fn,
+            attrs,
+            fields,
+            computed,
+            order,
+        })
+    }
+
+    /// The path `build()` constructs the value through: the struct, or the
+    /// enum variant.
+    pub fn ctor(&self) -> TokenStream {
+        let ident = self.ident;
+        match self.variant {
+            Some(variant) => quote!(#ident::#variant),
+            None => quote!(#ident),
+        }
+    }
+
+    /// A builder struct literal with each field set to `value(field)`, an
+    /// `Option` of the field's stored type.
+    pub fn builder_literal(&self, value: impl Fn(&Field) -> TokenStream) -> TokenStream {
+        let builder_ident = &self.builder_ident;
+        let idents = self.fields.iter().map(|field| &field.ident);
+        let values = self.fields.iter().map(value);
+        let phantom = self.phantom().map(|(_, init)| quote!(#init,));
+        let errors = self.fields.iter().filter_map(Field::deferred_error);
+        quote! {
+            #builder_ident {
+                #(#idents: #values,)*
+                #phantom
+                #(#errors: ::core::option::Option::None,)*
+            }
+        }
+    }
+
+    /// The built value, from the locals `build()` bound each field to.
+    pub fn construct(&self) -> TokenStream {
+        let ctor = self.ctor();
+        let members = (self.fields.iter().map(|field| &field.member))
+            .chain(self.computed.iter().map(|computed| &computed.member));
+        let idents = (self.fields.iter().map(|field| &field.ident))
+            .chain(self.computed.iter().map(|computed| &computed.ident));
+        quote! {
+            #ctor {
+                #(#members: #idents,)*
+            }
+        }
+    }
+
+    /// How the built type is referred to in doc comments.
+    pub fn target_name(&self) -> String {
+        match self.variant {
+            Some(variant) => format!("{}::{}", self.ident, variant),
+            None => self.ident.to_string(),
+        }
+    }
+
+    /// A marker field tying the builder to all of the generic parameters,
+    /// some of which may otherwise not appear in its fields: those only used
+    /// by other variants of an enum, or by the states of a typestate builder.
+    pub fn phantom(&self) -> Option<(TokenStream, TokenStream)> {
+        if self.generics.params.is_empty() || (self.variant.is_none() && !self.attrs.typestate) {
+            return None;
+        }
+        let ident = self.ident;
+        let (_, ty_generics, _) = self.generics.split_for_impl();
+        let serde_skip = self.attrs.serde.then(|| quote!(#[serde(skip)]));
+        Some((
+            quote!(#serde_skip __phantom: ::core::marker::PhantomData<fn() -> #ident #ty_generics>),
+            quote!(__phantom: ::core::marker::PhantomData),
+        ))
+    }
+
+    /// Whether a `try_setter(deferred)` field makes the builder hold on to
+    /// a conversion error until `build()`, in its `Field::deferred_error`.
+    pub fn defers_errors(&self) -> bool {
+        self.fields
+            .iter()
+            .any(|field| matches!(field.try_setter, Some((TrySetter::Deferred, _))))
+    }
+
+    /// `#[derive(...)]` for the traits requested with `builder(derive(...))`.
+    pub fn derives(&self) -> Option<TokenStream> {
+        let derives = &self.attrs.derives;
+        (!derives.is_empty()).then(|| quote!(#[derive(#(#derives),*)]))
+    }
+
+    fn derives_clone(&self) -> bool {
+        self.attrs
+            .derives
+            .iter()
+            .any(|path| path.segments.last().is_some_and(|s| s.ident == "Clone"))
+    }
+
+    /// The crate generated code takes `String`, `Vec` and `format!` from:
+    /// `alloc` with `builder(no_std)`, which the user's crate must declare
+    /// with `extern crate alloc`, and `std` otherwise.
+    pub fn alloc(&self) -> TokenStream {
+        if self.attrs.no_std {
+            quote!(::alloc)
+        } else {
+            quote!(::std)
+        }
+    }
+
+    /// A const builder is always consumed, see `constant::check`.
+    pub fn pattern(&self) -> Pattern {
+        if constant::is_used(self) {
+            return Pattern::Owned;
+        }
+        self.attrs
+            .pattern
+            .map_or(Pattern::Mutable, |(pattern, _)| pattern)
+    }
+
+    /// `const` for the functions of a `builder(const)` builder.
+    pub fn constness(&self) -> Option<TokenStream> {
+        constant::is_used(self).then(|| quote!(const))
+    }
+
+    /// The order in which `build()` binds fields to locals: fields with a
+    /// `default = "..."` expression and computed fields come last, each
+    /// after the fields its expression refers to.
+    pub fn build_order(&self) -> impl Iterator<Item = Binding<'_>> {
+        let bindings = order::bindings(&self.fields, &self.computed);
+        self.order.iter().map(move |&i| bindings[i])
+    }
+
+    /// Calls each `default_with = "..."` closure whose field is unset with
+    /// `builder`, before `build()` moves any field out of the builder.
+    pub fn default_with(&self, builder: &TokenStream) -> TokenStream {
+        let calls = self.fields.iter().filter_map(|field| {
+            let Some(DefaultValue::With(closure)) = &field.default else {
+                return None;
+            };
+            let ident = &field.ident;
+            let local = default_with_local(field);
+            Some(quote! {
+                let #local = if ::core::option::Option::is_none(&self.#ident) {
+                    ::core::option::Option::Some((#closure)(#builder))
+                } else {
+                    ::core::option::Option::None
+                };
+            })
+        });
+        quote!(#(#calls)*)
+    }
+
+    /// `let __default = <Struct as Default>::default();` if any field falls
+    /// back to the struct-level default.
+    pub fn struct_default(&self) -> Option<TokenStream> {
+        let uses_struct_default = self
+            .fields
+            .iter()
+            .any(|field| matches!(field.default, Some(DefaultValue::Struct)));
+        let ident = self.ident;
+        let (_, ty_generics, _) = self.generics.split_for_impl();
+        uses_struct_default.then(|| {
+            quote! {
+                let __default: #ident #ty_generics = ::core::default::Default::default();
+            }
+        })
+    }
+
+    /// The value `build()` uses for a field that was never set.
+    pub fn unset_value(&self, field: &Field) -> TokenStream {
+        let error_ident = &self.error_ident;
+        let member = &field.member;
+        match (&field.default, &field.kind) {
+            (Some(DefaultValue::Trait), _) => quote!(::core::default::Default::default()),
+            (Some(DefaultValue::Expr(expr)), _) => quote!(#expr),
+            (Some(DefaultValue::With(_)), _) => {
+                let local = default_with_local(field);
+                quote! {
+                    match #local {
+                        ::core::option::Option::Some(value) => value,
+                        ::core::option::Option::None => ::core::unreachable!(),
+                    }
+                }
+            }
+            (Some(DefaultValue::Struct), _) => quote!(__default.#member),
+            // In a constant, the panic is reported as a compile error.
+            (None, Kind::Plain) if constant::is_used(self) => {
+                let message = format!("missing required field `{}`", field.name());
+                quote!(::core::panic!(#message))
+            }
+            (None, Kind::Plain) => {
+                let variant = field.missing_variant();
+                self.fail(quote!(#error_ident::#variant))
+            }
+            (None, Kind::Optional(_)) => quote!(::core::option::Option::None),
+            (None, Kind::Each { .. }) => quote!(::core::default::Default::default()),
+            (None, Kind::Sub(sub)) if sub.optional => quote!(::core::option::Option::None),
+            // A nested builder that was never touched may still build, e.g.
+            // if all of its fields have defaults.
+            (None, Kind::Sub(sub)) => {
+                let target = &sub.target;
+                let method = Ident::new(BUILD_OWNED, Span::call_site());
+                self.build_nested(field, quote!(<#target>::builder().#method()))
+            }
+        }
+    }
+
+    /// Builds the nested builder of a `sub_builder` field with `build`, a
+    /// call of one of its hidden build functions, wrapping any error in the
+    /// field's variant of this builder's error.
+    pub fn build_nested(&self, field: &Field, build: TokenStream) -> TokenStream {
+        let error_ident = &self.error_ident;
+        let variant = field.nested_variant();
+        let fail = self.fail(quote!(#error_ident::#variant(err)));
+        quote! {
+            match #build {
+                ::core::result::Result::Ok(value) => value,
+                ::core::result::Result::Err(err) => {
+                    #fail;
+                }
+            }
+        }
+    }
+
+    /// Returns `error` from the hidden build functions, which report the
+    /// builder's own error whatever `build_fn(error = "...")` says.
+    pub fn fail(&self, error: TokenStream) -> TokenStream {
+        quote!(return ::core::result::Result::Err(#error))
+    }
+
+    /// The name of the function building the value.
+    pub fn build_fn_name(&self) -> Ident {
+        (self.attrs.build_fn.name.clone()).unwrap_or_else(|| format_ident!("build"))
+    }
+
+    pub fn build_fn_vis(&self) -> Visibility {
+        if self.attrs.build_fn.private {
+            Visibility::Inherited
+        } else {
+            self.vis.clone()
+        }
+    }
+
+    /// The built value `construct`, after passing it to the
+    /// `build_fn(post_build = "...")` hook if there is one.
+    pub fn post_build(&self, construct: TokenStream) -> TokenStream {
+        match &self.attrs.build_fn.post_build {
+            Some(post_build) => quote! {
+                {
+                    let mut built = #construct;
+                    #post_build(&mut built);
+                    built
+                }
+            },
+            None => construct,
+        }
+    }
+}
+
+/// The hidden aliases of a struct's builder and of its error, by which a
+/// `sub_builder` field finds them whatever `builder(name = "...")` says.
+pub fn alias_idents(ident: &Ident) -> (Ident, Ident) {
+    (
+        format_ident!("__{}Builder", ident.unraw()),
+        format_ident!("__{}BuilderError", ident.unraw()),
+    )
+}
+
+/// Defines the `alias_idents` of a struct's builder.
+fn aliases(cx: &Context) -> Option<TokenStream> {
+    if cx.variant.is_some() {
+        return None;
+    }
+    let vis = &cx.vis;
+    let builder_ident = &cx.builder_ident;
+    let error_ident = &cx.error_ident;
+    let (builder_alias, error_alias) = alias_idents(cx.ident);
+    // Bounds on the parameters of a type alias are not enforced.
+    let params = cx.generics.params.iter().map(|param| match param {
+        GenericParam::Lifetime(param) => param.lifetime.to_token_stream(),
+        GenericParam::Type(param) => param.ident.to_token_stream(),
+        GenericParam::Const(param) => {
+            let ident = &param.ident;
+            let ty = &param.ty;
+            quote!(const #ident: #ty)
+        }
+    });
+    let (_, ty_generics, _) = cx.generics.split_for_impl();
+    let error = (!constant::is_used(cx)).then(|| {
+        quote! {
+            #[doc(hidden)]
+            #[allow(dead_code)]
+            #vis type #error_alias = #error_ident;
+        }
+    });
+    Some(quote! {
+        #[doc(hidden)]
+        #[allow(dead_code)]
+        #vis type #builder_alias<#(#params),*> = #builder_ident #ty_generics;
+        #error
+    })
+}
+
+/// The local holding the result of a field's `default_with` closure.
+fn default_with_local(field: &Field) -> Ident {
+    format_ident!("__default_{}", field.ident.unraw())
+}
+
+pub fn derive(input: &DeriveInput) -> Result<TokenStream> {
+    match &input.data {
+        Data::Struct(DataStruct {
+            fields: Fields::Unit,
+            ..
+        }) => Err(Error::new_spanned(
+            &input.ident,
+            "`derive(Builder)` does not support unit structs, which have no fields to set",
+        )),
+        Data::Struct(data) => expand(&Context::new(input, None, &data.fields)?),
+        Data::Enum(data) => {
+            let mut builders = TokenStream::new();
+            for variant in &data.variants {
+                let attrs = VariantAttrs::parse(&variant.attrs)?;
+                if attrs.skip {
+                    continue;
+                }
+                // Tuple and unit variants have a builder only on request.
+                let message = match &variant.fields {
+                    Fields::Unnamed(_) if attrs.field_names.is_none() => Some(
+                        "`derive(Builder)` needs names for the setters of a tuple variant; \
+                         add `#[builder(field_names(...))]` to name its fields, or \
+                         `#[builder(skip)]` to leave this variant without a builder",
+                    ),
+                    Fields::Unit if !attrs.unit => Some(
+                        "`derive(Builder)` gives a unit variant a builder only on request; \
+                         add `#[builder(unit)]` for a builder without setters, or \
+                         `#[builder(skip)]` to leave this variant without a builder",
+                    ),
+                    Fields::Named(_) | Fields::Unnamed(_) if attrs.unit => {
+                        Some("`builder(unit)` requires a unit variant")
+                    }
+                    _ => None,
+                };
+                if let Some(message) = message {
+                    return Err(Error::new_spanned(&variant.ident, message));
+                }
+                let cx = Context::new(input, Some((&variant.ident, attrs)), &variant.fields)?;
+                builders.extend(expand(&cx)?);
+            }
+            Ok(builders)
+        }
+        Data::Union(data) => Err(Error::new_spanned(
+            data.union_token,
+            "`derive(Builder)` does not support unions",
+        )),
+    }
+}
+
+fn expand(cx: &Context) -> Result<TokenStream> {
+    constant::check(cx)?;
+    if cx.attrs.typestate {
+        return typestate::expand(cx);
+    }
+
+    let builder_struct = builder_struct(cx);
+    let builder_fn = builder_fn(cx);
+    let setters = cx.fields.iter().map(|field| setters(cx, field));
+    let build_fn = build_fn(cx);
+    // A const build() has no error to return.
+    let error_enum = (!constant::is_used(cx)).then(|| error::error_enum(cx));
+    let uninitialized = error::uninitialized_struct(cx);
+
+    let builder_ident = &cx.builder_ident;
+    let (impl_generics, ty_generics, where_clause) = cx.generics.split_for_impl();
+    // A value of the enum need not be of the builder's variant, so there is
+    // no conversion back into a variant's builder.
+    let convert = cx
+        .variant
+        .is_none()
+        .then(|| convert::expand(cx, &quote!(#builder_ident #ty_generics), |_| false));
+    let merge = merge::expand(cx);
+    let impl_header = quote!(impl #impl_generics #builder_ident #ty_generics #where_clause);
+    let introspect = introspect::expand(cx, impl_header.clone(), impl_header, |field| {
+        let ident = &field.ident;
+        quote!(self.#ident.is_some())
+    });
+    let from_env = env::expand(cx)?;
+    let parse_args = cli::expand(cx);
+    let flattened = cx
+        .fields
+        .iter()
+        .filter_map(|field| flatten::invoke(cx, field));
+    let flatten_macro = flatten::define(cx);
+    let owned_trait = owned_trait_def(cx);
+    let aliases = aliases(cx);
+    Ok(quote! {
+        #builder_struct
+        #builder_fn
+
+        impl #impl_generics #builder_ident #ty_generics #where_clause {
+            #(#setters)*
+            #build_fn
+        }
+
+        #aliases
+        #owned_trait
+        #(#flattened)*
+        #flatten_macro
+
+        #merge
+        #introspect
+        #from_env
+        #parse_args
+        #convert
+        #error_enum
+        #uninitialized
+    })
+}
+
+fn builder_struct(cx: &Context) -> TokenStream {
+    let vis = &cx.vis;
+    let builder_ident = &cx.builder_ident;
+    let generics = cx.generics;
+    let where_clause = &generics.where_clause;
+    let doc = format!("Builder for [`{}`].", cx.target_name());
+
+    // A builder deriving `Deserialize` can be loaded from a partial
+    // configuration, in which any field may be missing.
+    let serde = cx.attrs.serde;
+    let fields = cx.fields.iter().map(|field| {
+        let field_vis = &field.vis;
+        let ident = &field.ident;
+        let ty = field.stored_ty();
+        let serde_attrs = &field.serde_attrs;
+        let serde_default = serde.then(|| quote!(#[serde(default)]));
+        quote! {
+            #serde_default
+            #(#[#serde_attrs])*
+            #field_vis #ident: ::core::option::Option<#ty>,
+        }
+    });
+    // Immutable setters return a modified copy of the builder, unless the
+    // builder already derives `Clone` on request.
+    let derives = cx.derives();
+    let derive_clone = (cx.pattern() == Pattern::Immutable && !cx.derives_clone())
+        .then(|| quote!(#[derive(::core::clone::Clone)]));
+    let derive_deserialize = serde.then(|| quote!(#[derive(::serde::Deserialize)]));
+    let serde_attrs = &cx.attrs.serde_attrs;
+    let phantom = cx.phantom().map(|(field, _)| quote!(#field,));
+    let errors = cx
+        .fields
+        .iter()
+        .filter_map(Field::deferred_error)
+        .map(|slot| {
+            let error_ident = &cx.error_ident;
+            let serde_skip = serde.then(|| quote!(#[serde(skip)]));
+            quote! {
+                #serde_skip
+                #slot: ::core::option::Option<#error_ident>,
+            }
+        });
+
+    quote! {
+        #[doc = #doc]
+        #derives
+        #derive_clone
+        #derive_deserialize
+        #(#[#serde_attrs])*
+        #vis struct #builder_ident #generics #where_clause {
+            #(#fields)*
+            #phantom
+            #(#errors)*
+        }
+    }
+}
+
+fn builder_fn(cx: &Context) -> TokenStream {
+    let vis = &cx.vis;
+    let constness = cx.constness();
+    let ident = cx.ident;
+    let builder_ident = &cx.builder_ident;
+    let (impl_generics, ty_generics, where_clause) = cx.generics.split_for_impl();
+
+    // With `positional`, the required fields are only ever set here.
+    if cx.attrs.positional.is_some() {
+        let params: Vec<_> = cx
+            .fields
+            .iter()
+            .filter(|field| field.is_required())
+            .map(|field| {
+                let ident = &field.ident;
+                let generic = format_ident!("__{}", field.camel());
+                let param = setter::named_param(&field.ty, field.setter.into, ident, &generic);
+                (field, param)
+            })
+            .collect();
+        let generics = params
+            .iter()
+            .filter_map(|(_, param)| param.generics.as_ref());
+        let args = params.iter().map(|(field, param)| {
+            let ident = &field.ident;
+            let arg_ty = &param.arg_ty;
+            quote!(#ident: #arg_ty)
+        });
+        let literal = cx.builder_literal(|field| {
+            match params
+                .iter()
+                .find(|(required, _)| required.ident == field.ident)
+            {
+                Some((_, param)) => {
+                    let value = &param.value;
+                    quote!(::core::option::Option::Some(#value))
+                }
+                None => quote!(::core::option::Option::None),
+            }
+        });
+        let doc = format!(
+            "Returns a [`{}`] with the required fields set to the given values.",
+            builder_ident,
+        );
+        return quote! {
+            impl #impl_generics #ident #ty_generics #where_clause {
+                #[doc = #doc]
+                #vis #constness fn new<#(#generics),*>(#(#args),*) -> #builder_ident #ty_generics {
+                    #literal
+                }
+            }
+        };
+    }
+
+    let doc = format!("Returns a [`{}`] with no fields set.", builder_ident);
+    let builder_fn = &cx.builder_fn;
+    let literal = cx.builder_literal(|_| quote!(::core::option::Option::None));
+    quote! {
+        impl #impl_generics #ident #ty_generics #where_clause {
+            #[doc = #doc]
+            #vis #constness fn #builder_fn() -> #builder_ident #ty_generics {
+                #literal
+            }
+        }
+    }
+}
+
+fn setters(cx: &Context, field: &Field) -> TokenStream {
+    let vis = &cx.vis;
+    if let Some(accessor) = setter::sub_builder_accessor(vis, field) {
+        return accessor;
+    }
+    if cx.attrs.positional.is_some() && field.is_required() {
+        return TokenStream::new();
+    }
+
+    let Receiver {
+        receiver,
+        ret,
+        target,
+        copy,
+    } = setter::receiver(cx.pattern());
+
+    let constness = cx.constness();
+    let docs = &field.docs;
+    // Setting the field drops the error of a failed deferred `try_` setter.
+    let clear_error = field
+        .deferred_error()
+        .map(|slot| quote!(#target.#slot = ::core::option::Option::None;));
+    let setters = setter::setters(field, &target).into_iter().map(|setter| {
+        let Setter {
+            name,
+            generics,
+            args,
+            where_clause,
+            body,
+            ..
+        } = setter;
+        let span = name.span();
+        let setter = quote! {
+            #vis #constness fn #name<#(#generics),*>(#receiver, #args) -> #ret #where_clause {
+                #copy
+                #body
+                #clear_error
+                #target
+            }
+        };
+        let setter = setter::respan(setter, span);
+        quote! {
+            #(#docs)*
+            #setter
+        }
+    });
+
+    // A deferred `try_` setter keeps the chain going after a failed
+    // conversion, leaving `build()` to report it unless the field is set
+    // in the meantime.
+    let try_setter = match field.deferred_error() {
+        Some(slot) => setter::try_setter_target(field).map(|(name, ty)| {
+            let setter = &field.setter.name;
+            let error_ident = &cx.error_ident;
+            let alloc = cx.alloc();
+            let field_name = field.name();
+            quote! {
+                #(#docs)*
+                #vis fn #name<__V: ::core::convert::TryInto<#ty>>(#receiver, value: __V) -> #ret
+                where
+                    <__V as ::core::convert::TryInto<#ty>>::Error: ::core::fmt::Display,
+                {
+                    match ::core::convert::TryInto::try_into(value) {
+                        ::core::result::Result::Ok(value) => self.#setter(value),
+                        ::core::result::Result::Err(err) => {
+                            #copy
+                            if #target.#slot.is_none() {
+                                #target.#slot = ::core::option::Option::Some(
+                                    #error_ident::Conversion {
+                                        field: #alloc::borrow::ToOwned::to_owned(#field_name),
+                                        message: #alloc::string::ToString::to_string(&err),
+                                    },
+                                );
+                            }
+                            #target
+                        }
+                    }
+                }
+            }
+        }),
+        None => setter::fallible_try_setter(vis, field, &receiver, &ret),
+    };
+    quote!(#(#setters)* #try_setter)
+}
+
+fn build_fn(cx: &Context) -> TokenStream {
+    let ident = cx.ident;
+    let error_ident = &cx.error_ident;
+    let (_, ty_generics, _) = cx.generics.split_for_impl();
+    let owned = cx.pattern() == Pattern::Owned;
+
+    // Unless the builder is consumed, fields are cloned out of it, which for
+    // field types involving the struct's type parameters only works if those
+    // are `Clone`. Nested builders are built rather than cloned.
+    let clone_bounds: Vec<_> = cx
+        .fields
+        .iter()
+        .filter(|field| !matches!(field.kind, Kind::Sub(_)))
+        .filter(|field| !owned && ty::mentions_type_param(&field.ty, cx.generics))
+        .map(|field| {
+            let ty = &field.ty;
+            quote!(#ty: ::core::clone::Clone)
+        })
+        .collect();
+
+    // The validation hook sees the builder before any field is moved out of
+    // it, but only once all required fields are known to be set.
+    let builder_ref = if owned { quote!(&self) } else { quote!(self) };
+    let alloc = cx.alloc();
+    let validate = cx.attrs.build_fn.validate.as_ref().map(|validate| {
+        let checks = cx
+            .fields
+            .iter()
+            .filter(|field| field.is_required())
+            .map(|field| {
+                let field_ident = &field.ident;
+                let variant = field.missing_variant();
+                let fail = cx.fail(quote!(#error_ident::#variant));
+                quote! {
+                    if ::core::option::Option::is_none(&self.#field_ident) {
+                        #fail;
+                    }
+                }
+            });
+        let fail = cx.fail(quote! {
+            #error_ident::Validation(#alloc::string::ToString::to_string(&err))
+        });
+        quote! {
+            #(#checks)*
+            if let ::core::result::Result::Err(err) = #validate(#builder_ref) {
+                #fail;
+            }
+        }
+    });
+
+    let deferred = cx
+        .fields
+        .iter()
+        .filter_map(Field::deferred_error)
+        .map(|slot| {
+            let fail = cx.fail(quote!(::core::clone::Clone::clone(err)));
+            quote! {
+                if let ::core::option::Option::Some(err) = &self.#slot {
+                    #fail;
+                }
+            }
+        });
+
+    let default_with = cx.default_with(&builder_ref);
+    let struct_default = cx.struct_default();
+    let bindings = cx.build_order().map(|binding| {
+        let field = match binding {
+            Binding::Field(field) => field,
+            Binding::Computed(computed) => return computed.binding(),
+        };
+        let field_ident = &field.ident;
+        let unset = cx.unset_value(field);
+        if let Kind::Sub(sub) = &field.kind {
+            // Unless this builder is consumed, the nested one is built
+            // through a reference, which a consumed nested builder rejects
+            // at the field.
+            let (place, build) = if owned {
+                let method = Ident::new(BUILD_OWNED, Span::call_site());
+                (quote!(self.#field_ident), quote!(builder.#method()))
+            } else {
+                let span = field.ident.span();
+                let method = Ident::new(BUILD_BY_REF, span);
+                let unit = quote_spanned!(span=> ());
+                (
+                    quote!(&self.#field_ident),
+                    quote!(builder.#method::<#unit>()),
+                )
+            };
+            let mut built = cx.build_nested(field, build);
+            if sub.optional {
+                built = quote!(::core::option::Option::Some(#built));
+            }
+            quote! {
+                let #field_ident = match #place {
+                    ::core::option::Option::Some(builder) => #built,
+                    ::core::option::Option::None => #unset,
+                };
+            }
+        } else if owned {
+            quote! {
+                let #field_ident = match self.#field_ident {
+                    ::core::option::Option::Some(value) => value,
+                    ::core::option::Option::None => #unset,
+                };
+            }
+        } else {
+            quote! {
+                let #field_ident = match &self.#field_ident {
+                    ::core::option::Option::Some(value) => ::core::clone::Clone::clone(value),
+                    ::core::option::Option::None => #unset,
+                };
+            }
+        }
+    });
+    let construct = cx.construct();
+    let built = cx.post_build(construct);
+
+    let receiver = if owned { quote!(self) } else { quote!(&self) };
+    let vis = cx.build_fn_vis();
+    let name = cx.build_fn_name();
+
+    if constant::is_used(cx) {
+        let doc = format!(
+            "Builds a [`{}`], panicking if a required field was not set, which \
+             in a constant is a compile error.",
+            cx.target_name(),
+        );
+        return quote! {
+            #[doc = #doc]
+            #vis const fn #name(self) -> #ident #ty_generics {
+                #(#bindings)*
+                #built
+            }
+        };
+    }
+
+    let doc = format!(
+        "Builds a [`{}`], or reports the first required field that was not set.",
+        cx.target_name(),
+    );
+    let ret = quote!(::core::result::Result<#ident #ty_generics, #error_ident>);
+    let body = quote! {
+        #(#deferred)*
+        #validate
+        #default_with
+        #struct_default
+        #(#bindings)*
+        ::core::result::Result::Ok(#built)
+    };
+
+    // What an outer builder calls to build this one as a nested builder:
+    // the same as `build()`, but always returning this builder's own error,
+    // and there whether or not `build()` is renamed. A consumed builder
+    // cannot be built through a reference, so calling `__build_by_ref` on it
+    // is an error naming the pattern instead.
+    let build_owned = Ident::new(BUILD_OWNED, Span::call_site());
+    let build_by_ref = Ident::new(BUILD_BY_REF, Span::call_site());
+    let (hidden, call) = if owned {
+        let owned = owned_trait(cx);
+        let hidden = quote! {
+            #[doc(hidden)]
+            #[allow(dead_code)]
+            #vis fn #build_owned(self) -> #ret {
+                #body
+            }
+
+            #[doc(hidden)]
+            #[allow(dead_code)]
+            #vis fn #build_by_ref<__B: #owned>(&self) -> #ret {
+                ::core::unreachable!()
+            }
+        };
+        (hidden, quote!(self.#build_owned()))
+    } else {
+        let hidden = quote! {
+            #[doc(hidden)]
+            #[allow(dead_code)]
+            #vis fn #build_owned(self) -> #ret
+            where
+                #(#clone_bounds,)*
+            {
+                self.#build_by_ref::<()>()
+            }
+
+            #[doc(hidden)]
+            #[allow(dead_code)]
+            #vis fn #build_by_ref<__B>(&self) -> #ret
+            where
+                #(#clone_bounds,)*
+            {
+                #body
+            }
+        };
+        (hidden, quote!(self.#build_by_ref::<()>()))
+    };
+
+    let (ret, call) = match &cx.attrs.build_fn.error {
+        Some(error) => (
+            quote!(::core::result::Result<#ident #ty_generics, #error>),
+            convert_error(cx, call),
+        ),
+        None => (ret, call),
+    };
+
+    quote! {
+        #[doc = #doc]
+        #vis fn #name(#receiver) -> #ret
+        where
+            #(#clone_bounds,)*
+        {
+            #call
+        }
+
+        #hidden
+    }
+}
+
+/// `call` of a hidden build function, with its error converted into the
+/// type given with `build_fn(error = "...")`: a missing field through the
+/// `UninitializedFieldError`, anything else from the builder's error.
+fn convert_error(cx: &Context, call: TokenStream) -> TokenStream {
+    let error_ident = &cx.error_ident;
+    let uninitialized = error::uninitialized_ident(cx);
+    let missing = cx.fields.iter().filter(|f| f.is_required()).map(|f| {
+        let variant = f.missing_variant();
+        let name = f.name();
+        quote! {
+            #error_ident::#variant => ::core::convert::From::from(#uninitialized(#name)),
+        }
+    });
+    // Only convert from the builder's error if `build()` can fail for a
+    // reason other than a missing field, so that the error type need not
+    // implement `From` for it otherwise.
+    let fails_otherwise = cx.attrs.build_fn.validate.is_some()
+        || cx.defers_errors()
+        || (cx.fields.iter()).any(|f| matches!(f.kind, Kind::Sub(_)));
+    let otherwise = if fails_otherwise {
+        quote!(err => ::core::convert::From::from(err),)
+    } else {
+        quote!(_ => ::core::unreachable!(),)
+    };
+    quote! {
+        match #call {
+            ::core::result::Result::Ok(value) => ::core::result::Result::Ok(value),
+            ::core::result::Result::Err(err) => ::core::result::Result::Err(match err {
+                #(#missing)*
+                #otherwise
+            }),
+        }
+    }
+}
+
+/// The hidden method building a nested builder that is consumed.
+const BUILD_OWNED: &str = "__build_owned";
+
+/// The hidden method building a nested builder through a reference.
+const BUILD_BY_REF: &str = "__build_by_ref";
+
+/// The trait, implemented by nothing, that a consumed builder requires for
+/// being built through a reference.
+fn owned_trait(cx: &Context) -> Ident {
+    format_ident!("__{}Owned", cx.builder_ident)
+}
+
+/// Defines the `owned_trait` of a consumed builder.
+fn owned_trait_def(cx: &Context) -> Option<TokenStream> {
+    if cx.pattern() != Pattern::Owned || constant::is_used(cx) {
+        return None;
+    }
+    let vis = &cx.vis;
+    let owned = owned_trait(cx);
+    let message = format!(
+        "`{}` is consumed by `build()`, so it can only be nested in a builder \
+         with `pattern = \"owned\"`",
+        cx.builder_ident,
+    );
+    let label = "nested in a builder that is not consumed by `build()`";
+    let note = "a builder that is not consumed builds the nested one through a reference";
+    Some(quote! {
+        #[doc(hidden)]
+        #[diagnostic::on_unimplemented(message = #message, label = #label, note = #note)]
+        #vis trait #owned {}
+    })
+}
@@ -0,0 +1,406 @@
+use crate::attr::{FieldAttrs, FieldDefault, Merge, StructAttrs, TrySetter};
+use crate::ty::{self, Item};
+use proc_macro2::{Span, TokenStream};
+use quote::{format_ident, quote};
+use syn::ext::IdentExt;
+use syn::{Attribute, Error, Expr, Ident, Member, Path, Result, Type, Visibility};
+
+pub struct Field {
+    /// Name of the field in the builder, and of its setter by default.
+    pub ident: Ident,
+    /// The field of the struct itself: `ident` for named fields, the index
+    /// for tuple structs.
+    pub member: Member,
+    pub ty: Type,
+    pub kind: Kind,
+    pub default: Option<DefaultValue>,
+    pub setter: SetterOpts,
+    /// `merge = "..."`, with the span of the string for error reporting.
+    pub merge: Option<(Merge, Span)>,
+    /// `serde(...)` attributes for the builder's field.
+    pub serde_attrs: Vec<TokenStream>,
+    /// The environment variable `from_env()` reads this field from.
+    pub env: Option<EnvVar>,
+    /// `cli(skip)`: `parse_args()` has no flag for this field.
+    pub cli_skip: bool,
+    /// `try_setter`, with the span of the key for error reporting.
+    pub try_setter: Option<(TrySetter, Span)>,
+    /// Visibility of the builder's field; private unless `field(vis = "...")`.
+    pub vis: Visibility,
+    /// The field's doc comments, repeated on its setters.
+    pub docs: Vec<Attribute>,
+}
+
+pub struct EnvVar {
+    pub name: String,
+    /// What the variable is split on for a collection field.
+    pub separator: String,
+    /// Span of an explicit `env = "..."`; `None` if the name comes from the
+    /// struct's `env_prefix`.
+    pub span: Option<Span>,
+}
+
+/// The shape of a field, which decides what its setters look like.
+pub enum Kind {
+    /// Set as a whole; `build()` fails with a `Missing*` error if it was
+    /// never set and has no default.
+    Plain,
+    /// `Option<T>`: left as `None` unless set. Holds `T`.
+    Optional(Type),
+    /// `#[builder(each = "...")]`: starts out empty and is extended one item
+    /// at a time through the named setter.
+    Each { setter: Ident, item: Item },
+    /// `#[builder(sub_builder)]` or `#[builder(flatten)]`: configured through
+    /// a nested builder that is built along with this one.
+    Sub(Box<SubBuilder>),
+}
+
+pub struct SubBuilder {
+    /// The type the nested builder builds; `T` for an `Option<T>` field.
+    pub target: Type,
+    pub builder: Type,
+    pub error: Path,
+    /// The field is an `Option<T>`, left as `None` unless the nested builder
+    /// was accessed.
+    pub optional: bool,
+    /// For `builder(flatten)`, the macro generated alongside the nested
+    /// builder that forwards its setters from this one.
+    pub flatten: Option<Path>,
+}
+
+impl SubBuilder {
+    /// The option the field was declared with, for error messages.
+    pub fn attr(&self) -> &'static str {
+        if self.flatten.is_some() {
+            "flatten"
+        } else {
+            "sub_builder"
+        }
+    }
+}
+
+/// A field of the struct that the builder has no field or setter for:
+/// `#[builder(computed = "...")]`.
+pub struct Computed {
+    /// Name of the local `build()` binds the value to.
+    pub ident: Ident,
+    pub member: Member,
+    /// Evaluated once the fields it refers to by name are bound.
+    pub expr: Expr,
+}
+
+/// A field of the struct, as seen by the builder.
+pub enum StructField {
+    Builder(Box<Field>),
+    Computed(Box<Computed>),
+}
+
+/// Where `build()` gets the value of a field that was never set.
+pub enum DefaultValue {
+    /// `#[builder(default)]`: `Default::default()`.
+    Trait,
+    /// `#[builder(default = "...")]`, evaluated after the other fields so it
+    /// can refer to them by name.
+    Expr(Expr),
+    /// `#[builder(default_with = "...")]`: a closure called with the builder
+    /// before any field is moved out of it. It sees the fields as they were
+    /// set, not the defaults of other fields, so it takes no part in the
+    /// binding order.
+    With(Expr),
+    /// Struct-level `#[builder(default)]`: the field of the struct's own
+    /// `Default` value.
+    Struct,
+}
+
+/// `setter(...)` options after applying the struct-level defaults.
+pub struct SetterOpts {
+    /// Name of the setter for the field as a whole.
+    pub name: Ident,
+    pub into: bool,
+    pub strip_option: bool,
+    pub skip: bool,
+}
+
+impl Field {
+    pub fn from_syn(
+        field: &syn::Field,
+        member: Member,
+        ident: Ident,
+        struct_attrs: &StructAttrs,
+    ) -> Result<StructField> {
+        let ty = field.ty.clone();
+        let attrs = FieldAttrs::parse(&field.attrs)?;
+
+        if let Some(expr) = attrs.computed {
+            if let Some(span) = attrs.other {
+                return Err(Error::new(
+                    span,
+                    "`builder(computed = \"...\")` cannot be combined with other options",
+                ));
+            }
+            return Ok(StructField::Computed(Box::new(Computed {
+                ident,
+                member,
+                expr,
+            })));
+        }
+
+        if let (Some(_), Some(span)) = (attrs.sub_builder, attrs.flatten) {
+            return Err(Error::new(
+                span,
+                "`builder(flatten)` cannot be combined with `builder(sub_builder)`",
+            ));
+        }
+        let kind = if let Some(span) = attrs.sub_builder.or(attrs.flatten) {
+            let name = if attrs.flatten.is_some() {
+                "flatten"
+            } else {
+                "sub_builder"
+            };
+            if attrs.each.is_some() {
+                return Err(Error::new(
+                    span,
+                    format!(
+                        "`builder({})` cannot be combined with `builder(each = \"...\")`",
+                        name,
+                    ),
+                ));
+            }
+            let (target, optional) = match ty::option_inner(&ty) {
+                Some(inner) => (inner.clone(), true),
+                None => (ty.clone(), false),
+            };
+            let Some((builder, error)) = ty::builder_of(&target) else {
+                return Err(Error::new_spanned(
+                    &ty,
+                    format!(
+                        "`builder({})` requires a struct type such as `Limits` or `Option<Limits>`",
+                        name,
+                    ),
+                ));
+            };
+            let flatten = attrs.flatten.and_then(|_| ty::flatten_macro_of(&target));
+            Kind::Sub(Box::new(SubBuilder {
+                target,
+                builder,
+                error,
+                optional,
+                flatten,
+            }))
+        } else if let Some(setter) = attrs.each {
+            Kind::Each {
+                setter,
+                item: ty::collection_item(&ty),
+            }
+        } else if let Some(inner) = ty::option_inner(&ty) {
+            Kind::Optional(inner.clone())
+        } else {
+            Kind::Plain
+        };
+
+        let default = match attrs.default {
+            Some(FieldDefault::Trait) => Some(DefaultValue::Trait),
+            Some(FieldDefault::Expr(expr)) => Some(DefaultValue::Expr(expr)),
+            Some(FieldDefault::With(expr)) => Some(DefaultValue::With(expr)),
+            None if struct_attrs.default => Some(DefaultValue::Struct),
+            None => None,
+        };
+
+        let setter = SetterOpts {
+            name: attrs.setter.name.unwrap_or_else(|| ident.clone()),
+            into: attrs
+                .setter
+                .into
+                .or(struct_attrs.setter.into)
+                .unwrap_or(false),
+            strip_option: (attrs.setter.strip_option)
+                .or(struct_attrs.setter.strip_option)
+                .unwrap_or(true),
+            skip: attrs.setter.skip,
+        };
+
+        if let Some((Merge::Append, span)) = attrs.merge {
+            if !matches!(kind, Kind::Each { .. }) {
+                return Err(Error::new(
+                    span,
+                    "`builder(merge = \"append\")` requires `builder(each = \"...\")`",
+                ));
+            }
+        }
+
+        if let Some((_, span)) = attrs.try_setter {
+            if setter.skip {
+                return Err(Error::new(
+                    span,
+                    "`builder(try_setter)` cannot be combined with `setter(skip)`",
+                ));
+            }
+            if matches!(kind, Kind::Each { .. } | Kind::Sub(_)) {
+                return Err(Error::new(
+                    span,
+                    "`builder(try_setter)` requires a field that is set as a whole",
+                ));
+            }
+        }
+
+        let separator = attrs
+            .env_separator
+            .or_else(|| struct_attrs.env_separator.clone())
+            .unwrap_or_else(|| ",".to_owned());
+        if let (Some((_, span)), Some(_)) = (&attrs.env, attrs.env_skip) {
+            return Err(Error::new(
+                *span,
+                "`builder(env = \"...\")` cannot be combined with `builder(env(skip))`",
+            ));
+        }
+        let env = match (attrs.env, &struct_attrs.env_prefix) {
+            (Some((name, span)), _) => Some(EnvVar {
+                name,
+                separator,
+                span: Some(span),
+            }),
+            // Nested builders and fields without a setter are not meant to
+            // be set from outside, so a prefix alone does not cover them, nor
+            // fields opting out with `env(skip)`.
+            (None, Some(prefix))
+                if attrs.env_skip.is_none() && !setter.skip && !matches!(kind, Kind::Sub(_)) =>
+            {
+                Some(EnvVar {
+                    name: format!("{}{}", prefix, ident.unraw().to_string().to_uppercase()),
+                    separator,
+                    span: None,
+                })
+            }
+            (None, _) => None,
+        };
+
+        let field = Field {
+            ident,
+            member,
+            ty,
+            kind,
+            default,
+            setter,
+            merge: attrs.merge,
+            serde_attrs: attrs.serde_attrs,
+            env,
+            cli_skip: attrs.cli_skip,
+            try_setter: attrs.try_setter,
+            vis: attrs.vis.unwrap_or(Visibility::Inherited),
+            docs: field
+                .attrs
+                .iter()
+                .filter(|attr| attr.path().is_ident("doc"))
+                .cloned()
+                .collect(),
+        };
+        if field.setter.skip && field.is_required() {
+            return Err(Error::new_spanned(
+                &field.ident,
+                "`setter(skip)` requires the field to have a default value",
+            ));
+        }
+        if let Some(attr) = field.serde_attrs.first() {
+            if !struct_attrs.serde {
+                return Err(Error::new_spanned(
+                    attr,
+                    "`builder(serde(...))` on a field requires `builder(serde)` on the struct",
+                ));
+            }
+        }
+        Ok(StructField::Builder(Box::new(field)))
+    }
+
+    pub fn is_required(&self) -> bool {
+        matches!(self.kind, Kind::Plain) && self.default.is_none()
+    }
+
+    /// The type the builder stores this field as, inside an `Option`.
+    pub fn stored_ty(&self) -> &Type {
+        match &self.kind {
+            Kind::Sub(sub) => &sub.builder,
+            _ => &self.ty,
+        }
+    }
+
+    /// Name of the error variant wrapping a nested builder's error. A field
+    /// of a tuple struct, `_0`, gets the variant `Field0`.
+    pub fn nested_variant(&self) -> Ident {
+        let camel = self.camel();
+        let name = if camel.starts_with(|c: char| c.is_ascii_digit()) {
+            format!("Field{}", camel)
+        } else {
+            camel
+        };
+        Ident::new(&name, Span::call_site())
+    }
+
+    /// For a `try_setter(deferred)` field, the builder field holding the
+    /// error of the first `try_` setter call that failed since the field was
+    /// last set.
+    pub fn deferred_error(&self) -> Option<Ident> {
+        match self.try_setter {
+            Some((TrySetter::Deferred, _)) => Some(format_ident!("__{}_error", self.ident.unraw())),
+            _ => None,
+        }
+    }
+
+    /// Name of the error variant reported when this field is missing.
+    pub fn missing_variant(&self) -> Ident {
+        Ident::new(&format!("Missing{}", self.camel()), Span::call_site())
+    }
+
+    /// Field name in UpperCamelCase, for deriving type and variant names.
+    pub fn camel(&self) -> String {
+        upper_camel(&self.name())
+    }
+
+    /// Field name as written by the user, without any `r#` prefix.
+    pub fn name(&self) -> String {
+        self.ident.unraw().to_string()
+    }
+}
+
+fn upper_camel(snake: &str) -> String {
+    snake
+        .split('_')
+        .flat_map(|word| {
+            let mut chars = word.chars();
+            let first = chars.next().map(|c| c.to_ascii_uppercase());
+            first.into_iter().chain(chars)
+        })
+        .collect()
+}
+
+impl Computed {
+    /// `let` statement binding the computed value in `build()`.
+    pub fn binding(&self) -> TokenStream {
+        let ident = &self.ident;
+        let expr = &self.expr;
+        quote!(let #ident = #expr;)
+    }
+}
+
+/// `camel` in snake_case. A run of capitals is one word, as in
+/// `HTTPRequest` to `http_request`.
+pub fn snake_case(camel: &str) -> String {
+    let chars: Vec<char> = camel.chars().collect();
+    let mut snake = String::new();
+    for (i, &c) in chars.iter().enumerate() {
+        if c.is_ascii_uppercase() {
+            let prev = i.checked_sub(1).map(|prev| chars[prev]);
+            let next = chars.get(i + 1);
+            // The last capital of a run starts the next word.
+            let after_word = prev.is_some_and(|prev| !prev.is_ascii_uppercase() && prev != '_');
+            let ends_run = prev.is_some_and(|prev| prev.is_ascii_uppercase())
+                && next.is_some_and(char::is_ascii_lowercase);
+            if after_word || ends_run {
+                snake.push('_');
+            }
+            snake.push(c.to_ascii_lowercase());
+        } else {
+            snake.push(c);
+        }
+    }
+    snake
+}
@@ -0,0 +1,239 @@
+//! `#[builder(flatten)]`: the setters of a nested builder, forwarded from the
+//! outer builder as if the nested struct's fields were declared there.
+//!
+//! The outer derive cannot see the fields of the nested struct, so every
+//! struct's builder comes with a hidden `macro_rules!` that generates the
+//! forwarding setters, which the outer derive invokes. They are inherent
+//! methods like any other setter, so a clash between two setters is reported
+//! by rustc as a duplicate definition pointing at both fields. The types they
+//! take are resolved where the outer struct is defined.
+//!
+//! The nested struct's own `sub_builder` accessors are forwarded too, and its
+//! `flatten` fields by invoking their macros in turn, whose setters call the
+//! ones forwarded to the nested builder.
+
+use crate::attr::Pattern;
+use crate::expand::Context;
+use crate::field::{Field, Kind};
+use crate::setter::{self, Receiver, Setter};
+use proc_macro2::{Span, TokenStream};
+use quote::{format_ident, quote};
+use syn::ext::IdentExt;
+use syn::Ident;
+
+pub fn macro_ident(ident: &Ident) -> Ident {
+    format_ident!("__{}BuilderFlatten", ident.unraw())
+}
+
+/// Defines the macro for the builder of a struct. It is invoked with the
+/// outer builder's pattern, the visibility of its setters, the field holding
+/// the nested builder, the nested struct's type and the header of the `impl`
+/// block to put the setters in. When the macro of a struct flattening this
+/// one invokes it, it is also given the pattern of that struct's builder,
+/// whose forwarded setters the outer builder's setters call.
+pub fn define(cx: &Context) -> Option<TokenStream> {
+    if cx.variant.is_some() {
+        return None;
+    }
+
+    let macro_ident = macro_ident(cx.ident);
+    let unsupported = if cx.attrs.typestate {
+        Some("`builder(flatten)` is not supported for a struct with `builder(typestate)`")
+    } else if cx.attrs.positional.is_some() {
+        Some("`builder(flatten)` is not supported for a struct with `builder(positional)`")
+    } else if !cx.generics.params.is_empty() {
+        // The forwarded setters would refer to parameters that the outer
+        // builder does not have.
+        Some("`builder(flatten)` is not supported for a struct with generic parameters")
+    } else {
+        None
+    };
+    let arms = match unsupported {
+        Some(message) => quote! {
+            ($($args:tt)*) => {
+                ::core::compile_error!(#message);
+            };
+        },
+        None => {
+            let arms = PATTERNS.into_iter().map(|pattern| {
+                let name = pattern_name(pattern);
+                let setters = forward_all(cx, pattern, cx.pattern());
+                let via = PATTERNS.into_iter().map(|via| {
+                    let via_name = pattern_name(via);
+                    let setters = forward_all(cx, pattern, via);
+                    quote! {
+                        (#name, #via_name, $vis:vis, $field:ident, $inner:ty, { $($header:tt)* }) => {
+                            #setters
+                        };
+                    }
+                });
+                quote! {
+                    (#name, $vis:vis, $field:ident, $inner:ty, { $($header:tt)* }) => {
+                        #setters
+                    };
+                    #(#via)*
+                }
+            });
+            quote!(#(#arms)*)
+        }
+    };
+
+    Some(quote! {
+        #[doc(hidden)]
+        #[allow(unused_macros)]
+        macro_rules! #macro_ident {
+            #arms
+        }
+
+        #[doc(hidden)]
+        #[allow(unused_imports)]
+        pub(crate) use #macro_ident;
+    })
+}
+
+const PATTERNS: [Pattern; 3] = [Pattern::Mutable, Pattern::Owned, Pattern::Immutable];
+
+/// The setters and accessors of every field on a builder with the given
+/// `pattern`, calling those of a nested builder with the pattern `via`,
+/// followed by the invocations of the macros of this struct's own
+/// `flatten` fields.
+fn forward_all(cx: &Context, pattern: Pattern, via: Pattern) -> TokenStream {
+    let setters = cx.fields.iter().map(|field| match &field.kind {
+        Kind::Sub(sub) if sub.flatten.is_none() => forward_accessor(field),
+        _ => forward(field, pattern, via),
+    });
+    let nested = cx.fields.iter().filter_map(|field| {
+        let Kind::Sub(sub) = &field.kind else {
+            return None;
+        };
+        let path = sub.flatten.as_ref()?;
+        if field.setter.skip {
+            return None;
+        }
+        let name = pattern_name(pattern);
+        let via = pattern_name(cx.pattern());
+        Some(quote! {
+            #path! { #name, #via, $vis, $field, $inner, { $($header)* } }
+        })
+    });
+    quote! {
+        $($header)* {
+            #(#setters)*
+        }
+        #(#nested)*
+    }
+}
+
+/// The pattern as named in the macro's arms.
+fn pattern_name(pattern: Pattern) -> Ident {
+    let name = match pattern {
+        Pattern::Mutable => "mutable",
+        Pattern::Owned => "owned",
+        Pattern::Immutable => "immutable",
+    };
+    Ident::new(name, Span::call_site())
+}
+
+/// The accessor of a `sub_builder` field, which takes `&mut self` whatever
+/// the pattern, reaching through the nested builder held in `$field`.
+fn forward_accessor(field: &Field) -> TokenStream {
+    let Kind::Sub(sub) = &field.kind else {
+        return TokenStream::new();
+    };
+    if field.setter.skip {
+        return TokenStream::new();
+    }
+    let name = &field.setter.name;
+    let builder = &sub.builder;
+    let docs = &field.docs;
+    quote! {
+        #(#docs)*
+        $vis fn #name(&mut self) -> &mut #builder {
+            ::core::option::Option::get_or_insert_with(&mut self.$field, <$inner>::builder).#name()
+        }
+    }
+}
+
+/// Setters on a builder with the given `pattern`, each taking the nested
+/// builder out of `$field`, or a new one, and passing its arguments on to the
+/// nested builder's setter of the same name, which has the pattern `via`.
+fn forward(field: &Field, pattern: Pattern, via: Pattern) -> TokenStream {
+    let Receiver {
+        receiver,
+        ret,
+        target,
+        copy,
+    } = setter::receiver(pattern);
+    let docs = &field.docs;
+
+    let setters = setter::setters(field, &quote!(__inner))
+        .into_iter()
+        .map(|setter| {
+            let Setter {
+                name,
+                generics,
+                args,
+                arg_names,
+                where_clause,
+                ..
+            } = setter;
+            let call = quote!(__inner.#name(#(#arg_names),*));
+            let apply = match via {
+                Pattern::Mutable => quote! {
+                    {
+                        let mut __inner = __inner;
+                        #call;
+                        __inner
+                    }
+                },
+                Pattern::Owned | Pattern::Immutable => call,
+            };
+            let span = name.span();
+            let setter = quote! {
+                $vis fn #name<#(#generics),*>(#receiver, #args) -> #ret #where_clause {
+                    #copy
+                    let __inner = ::core::option::Option::unwrap_or_else(
+                        ::core::option::Option::take(&mut #target.$field),
+                        <$inner>::builder,
+                    );
+                    let __inner = #apply;
+                    #target.$field = ::core::option::Option::Some(__inner);
+                    #target
+                }
+            };
+            let setter = setter::respan(setter, span);
+            quote! {
+                #(#docs)*
+                #setter
+            }
+        });
+    quote!(#(#setters)*)
+}
+
+/// Invokes the macro of a `builder(flatten)` field's nested builder, putting
+/// its setters on this builder.
+pub fn invoke(cx: &Context, field: &Field) -> Option<TokenStream> {
+    let Kind::Sub(sub) = &field.kind else {
+        return None;
+    };
+    let path = sub.flatten.as_ref()?;
+    if field.setter.skip {
+        return None;
+    }
+
+    let vis = &cx.vis;
+    let ident = &field.ident;
+    let target = &sub.target;
+    let pattern = pattern_name(cx.pattern());
+    let builder_ident = &cx.builder_ident;
+    let (impl_generics, ty_generics, where_clause) = cx.generics.split_for_impl();
+    Some(quote! {
+        #path! {
+            #pattern,
+            #vis,
+            #ident,
+            #target,
+            { impl #impl_generics #builder_ident #ty_generics #where_clause }
+        }
+    })
+}
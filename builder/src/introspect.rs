@@ -0,0 +1,119 @@
+//! Methods reporting which fields of a builder are set, and a `FIELDS` table
+//! describing them, e.g. for showing what is left to fill in before `build()`.
+
+use crate::expand::Context;
+use crate::field::{Field, Kind};
+use crate::ty;
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::ext::IdentExt;
+
+/// Generates `FIELDS` in an `impl` block with the header `fields_impl`, and
+/// the methods in one with the header `methods_impl`, `is_set(field)` telling
+/// whether a field is set.
+pub fn expand(
+    cx: &Context,
+    fields_impl: TokenStream,
+    methods_impl: TokenStream,
+    is_set: impl Fn(&Field) -> TokenStream,
+) -> TokenStream {
+    let vis = &cx.vis;
+    let alloc = cx.alloc();
+    let builder_ident = &cx.builder_ident;
+    let info_ident = format_ident!("{}FieldInfo", builder_ident);
+    let kind_ident = format_ident!("{}FieldKind", builder_ident);
+
+    let required: Vec<&Field> = cx.fields.iter().filter(|f| f.is_required()).collect();
+    let missing = required.iter().map(|field| {
+        let is_set = is_set(field);
+        let name = field.name();
+        quote! {
+            if !#is_set {
+                missing.push(#name);
+            }
+        }
+    });
+    let required_set = required.iter().map(|field| is_set(field));
+
+    let is_set = cx.fields.iter().map(|field| {
+        let method = format_ident!("is_set_{}", field.ident.unraw());
+        let doc = format!("Whether the field `{}` has been set.", field.name());
+        let is_set = is_set(field);
+        quote! {
+            #[doc = #doc]
+            #vis fn #method(&self) -> ::core::primitive::bool {
+                #is_set
+            }
+        }
+    });
+
+    let infos = cx.fields.iter().map(|field| {
+        let name = field.name();
+        let ty = ty::display(&field.ty);
+        let kind = match field.kind {
+            Kind::Each { .. } => quote!(Each),
+            _ if field.is_required() => quote!(Required),
+            _ => quote!(Optional),
+        };
+        quote! {
+            #info_ident {
+                name: #name,
+                ty: #ty,
+                kind: #kind_ident::#kind,
+            }
+        }
+    });
+
+    let info_doc = format!("Describes one field of [`{}`].", builder_ident);
+    let kind_doc = format!(
+        "Whether [`{}::{}`] needs a field to be set.",
+        builder_ident,
+        cx.build_fn_name(),
+    );
+
+    quote! {
+        #[doc = #info_doc]
+        #[derive(::core::fmt::Debug, ::core::clone::Clone, ::core::marker::Copy, ::core::cmp::PartialEq, ::core::cmp::Eq)]
+        #vis struct #info_ident {
+            /// The field's name.
+            #vis name: &'static ::core::primitive::str,
+            /// The field's type, as written in the struct.
+            #vis ty: &'static ::core::primitive::str,
+            #vis kind: #kind_ident,
+        }
+
+        #[doc = #kind_doc]
+        #[derive(::core::fmt::Debug, ::core::clone::Clone, ::core::marker::Copy, ::core::cmp::PartialEq, ::core::cmp::Eq)]
+        #vis enum #kind_ident {
+            /// `build()` fails unless the field is set.
+            Required,
+            /// The field is left as `None` or gets a default if unset.
+            Optional,
+            /// A collection extended one item at a time, empty if unset.
+            Each,
+        }
+
+        #fields_impl {
+            /// Every field of the builder, in declaration order.
+            #vis const FIELDS: &'static [#info_ident] = &[#(#infos),*];
+        }
+
+        #methods_impl {
+            /// The names of the required fields that are not set yet, in
+            /// declaration order.
+            #vis fn missing_fields(&self) -> #alloc::vec::Vec<&'static ::core::primitive::str> {
+                let mut missing = #alloc::vec::Vec::new();
+                #(#missing)*
+                missing
+            }
+
+            /// Whether every required field is set. `build()` may still fail,
+            /// e.g. in a nested builder or a validation hook.
+            #vis fn is_complete(&self) -> ::core::primitive::bool {
+                true #(&& #required_set)*
+            }
+
+            #(#is_set)*
+        }
+    }
+}
@@ -1,8 +1,28 @@
+mod attr;
+mod cli;
+mod constant;
+mod convert;
+mod env;
+mod error;
+mod expand;
+mod field;
+mod flatten;
+mod introspect;
+mod merge;
+mod order;
+mod parse;
+mod setter;
+mod ty;
+mod typestate;
+
 use proc_macro::TokenStream;
+use syn::{parse_macro_input, DeriveInput};
 
-#[proc_macro_derive(Builder)]
+#[proc_macro_derive(Builder, attributes(builder))]
 pub fn derive(input: TokenStream) -> TokenStream {
-    let _ = input;
+    let input = parse_macro_input!(input as DeriveInput);
 
-    unimplemented!()
+    expand::derive(&input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
 }
@@ -0,0 +1,71 @@
+//! `merge()` and `or()` for layering builders filled from several sources.
+
+use crate::attr::Merge;
+use crate::expand::Context;
+use crate::field::Kind;
+use proc_macro2::TokenStream;
+use quote::quote;
+
+pub fn expand(cx: &Context) -> TokenStream {
+    let vis = &cx.vis;
+    let builder_ident = &cx.builder_ident;
+    let (impl_generics, ty_generics, where_clause) = cx.generics.split_for_impl();
+
+    let merges = cx.fields.iter().map(|field| {
+        let ident = &field.ident;
+        // A deferred conversion error goes along with the field: `other`'s
+        // replaces this one's if `other` set the field or tried to.
+        let error = field.deferred_error().map(|slot| {
+            quote! {
+                if other.#ident.is_some() || other.#slot.is_some() {
+                    self.#slot = other.#slot;
+                }
+            }
+        });
+        let merge = field.merge.map_or(Merge::Replace, |(merge, _)| merge);
+        let merge = match (&field.kind, merge) {
+            (Kind::Each { .. }, Merge::Append) => quote! {
+                if let ::core::option::Option::Some(items) = other.#ident {
+                    ::core::iter::Extend::extend(
+                        self.#ident.get_or_insert_with(::core::default::Default::default),
+                        items,
+                    );
+                }
+            },
+            (Kind::Sub(_), _) => quote! {
+                match (&mut self.#ident, other.#ident) {
+                    (::core::option::Option::Some(nested), ::core::option::Option::Some(other)) => {
+                        nested.merge(other);
+                    }
+                    (slot, other @ ::core::option::Option::Some(_)) => *slot = other,
+                    (_, ::core::option::Option::None) => {}
+                }
+            },
+            _ => quote! {
+                if other.#ident.is_some() {
+                    self.#ident = other.#ident;
+                }
+            },
+        };
+        quote!(#error #merge)
+    });
+
+    quote! {
+        impl #impl_generics #builder_ident #ty_generics #where_clause {
+            /// Overrides the fields of `self` with those set in `other`.
+            /// Collections marked `merge = "append"` get the items of `other`
+            /// added after their own, and nested builders are merged in turn.
+            #vis fn merge(&mut self, other: Self) -> &mut Self {
+                #(#merges)*
+                self
+            }
+
+            /// Fills in the fields not set in `self` from `fallback`.
+            #vis fn or(self, fallback: Self) -> Self {
+                let mut merged = fallback;
+                merged.merge(self);
+                merged
+            }
+        }
+    }
+}
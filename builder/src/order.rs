@@ -0,0 +1,165 @@
+//! The order in which `build()` binds the fields of the struct to locals.
+
+use crate::field::{Computed, DefaultValue, Field};
+use proc_macro2::{Literal, TokenStream, TokenTree};
+use quote::ToTokens;
+use syn::ext::IdentExt;
+use syn::{Error, Expr, Ident, Lit, Result};
+
+/// A field `build()` binds a local for.
+#[derive(Clone, Copy)]
+pub enum Binding<'a> {
+    Field(&'a Field),
+    Computed(&'a Computed),
+}
+
+impl Binding<'_> {
+    fn ident(&self) -> &Ident {
+        match self {
+            Binding::Field(field) => &field.ident,
+            Binding::Computed(computed) => &computed.ident,
+        }
+    }
+
+    /// The expression giving the field its value, which may refer to other
+    /// fields by name.
+    fn expr(&self) -> Option<&Expr> {
+        match self {
+            Binding::Field(field) => match &field.default {
+                Some(DefaultValue::Expr(expr)) => Some(expr),
+                _ => None,
+            },
+            Binding::Computed(computed) => Some(&computed.expr),
+        }
+    }
+}
+
+/// Indices into the fields followed by the computed fields, in binding order:
+/// the fields without an expression first, then those with one, each after
+/// the fields its expression refers to. A cycle is reported on the expression
+/// of the first field in it.
+pub fn sort(fields: &[Field], computed: &[Computed]) -> Result<Vec<usize>> {
+    let bindings = bindings(fields, computed);
+    let (mut order, mut pending): (Vec<usize>, Vec<usize>) =
+        (0..bindings.len()).partition(|&i| bindings[i].expr().is_none());
+
+    // For each binding, the pending bindings its expression refers to. Its
+    // own name cannot refer to the local it is being bound to, so it names
+    // something else, such as a function of the same name.
+    let deps: Vec<Vec<usize>> = bindings
+        .iter()
+        .enumerate()
+        .map(|(j, binding)| match binding.expr() {
+            Some(expr) => pending
+                .iter()
+                .copied()
+                .filter(|&i| i != j)
+                .filter(|&i| refers_to(expr.to_token_stream(), bindings[i].ident(), false))
+                .collect(),
+            None => Vec::new(),
+        })
+        .collect();
+
+    while !pending.is_empty() {
+        let ready = pending
+            .iter()
+            .position(|&i| deps[i].iter().all(|dep| order.contains(dep)));
+        match ready {
+            Some(position) => order.push(pending.remove(position)),
+            None => return Err(cycle(&bindings, &deps, &order, pending[0])),
+        }
+    }
+    Ok(order)
+}
+
+pub fn bindings<'a>(fields: &'a [Field], computed: &'a [Computed]) -> Vec<Binding<'a>> {
+    let fields = fields.iter().map(Binding::Field);
+    fields
+        .chain(computed.iter().map(Binding::Computed))
+        .collect()
+}
+
+/// Follows unresolved dependencies from `start` until one repeats.
+fn cycle(bindings: &[Binding], deps: &[Vec<usize>], done: &[usize], start: usize) -> Error {
+    let mut path = vec![start];
+    let mut current = start;
+    loop {
+        current = *deps[current]
+            .iter()
+            .find(|dep| !done.contains(dep))
+            .expect("a binding that is not ready has an unresolved dependency");
+        if let Some(begin) = path.iter().position(|&i| i == current) {
+            let cycle = &path[begin..];
+            let names: Vec<String> = cycle
+                .iter()
+                .chain(Some(&current))
+                .map(|&i| format!("`{}`", bindings[i].ident().unraw()))
+                .collect();
+            let expr = bindings[cycle[0]]
+                .expr()
+                .expect("only expressions have dependencies");
+            return Error::new_spanned(
+                expr,
+                format!("cyclic dependency between defaults: {}", names.join(" -> ")),
+            );
+        }
+        path.push(current);
+    }
+}
+
+/// Whether `tokens` mention `ident` as a name of its own, rather than as a
+/// field or method following a `.` or as a segment of a path such as
+/// `defaults::ident()`. Inside a macro invocation, a string literal refers
+/// to `ident` if it captures it as `{ident}`, as `format!` does.
+fn refers_to(tokens: TokenStream, ident: &Ident, in_macro: bool) -> bool {
+    let is_punct = |tt: Option<&TokenTree>, ch: char| matches!(tt, Some(TokenTree::Punct(punct)) if punct.as_char() == ch);
+    let tokens: Vec<TokenTree> = tokens.into_iter().collect();
+    let path_sep = |at: usize| is_punct(tokens.get(at), ':') && is_punct(tokens.get(at + 1), ':');
+    let mut after_dot = false;
+    let mut after_macro_name = false;
+    for (i, tt) in tokens.iter().enumerate() {
+        let prev = i.checked_sub(1).map(|prev| &tokens[prev]);
+        let in_path = (i >= 2 && path_sep(i - 2)) || path_sep(i + 1);
+        match tt {
+            TokenTree::Ident(name) if !after_dot && !in_path && name == ident => return true,
+            TokenTree::Group(group)
+                if refers_to(group.stream(), ident, in_macro || after_macro_name) =>
+            {
+                return true
+            }
+            TokenTree::Literal(lit) if in_macro && captures(lit, ident) => return true,
+            _ => {}
+        }
+        after_macro_name = is_punct(Some(tt), '!') && matches!(prev, Some(TokenTree::Ident(_)));
+        // The second `.` of a range `..` is not a field access.
+        after_dot = is_punct(Some(tt), '.') && !is_punct(prev, '.');
+    }
+    false
+}
+
+/// Whether `lit` is a string with a `{ident}` or `{ident:...}` argument.
+fn captures(lit: &Literal, ident: &Ident) -> bool {
+    let Lit::Str(lit) = Lit::new(lit.clone()) else {
+        return false;
+    };
+    let ident = ident.unraw().to_string();
+    let value = lit.value();
+    let mut rest = value.as_str();
+    while let Some(start) = rest.find('{') {
+        rest = &rest[start + 1..];
+        // `{{` is an escaped brace.
+        if let Some(escaped) = rest.strip_prefix('{') {
+            rest = escaped;
+            continue;
+        }
+        let Some(end) = rest.find('}') else {
+            break;
+        };
+        let arg = rest[..end].split(':').next().unwrap_or_default().trim();
+        if arg.strip_prefix("r#").unwrap_or(arg) == ident {
+            return true;
+        }
+        rest = &rest[end + 1..];
+    }
+    false
+}
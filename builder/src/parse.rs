@@ -0,0 +1,59 @@
+//! Parsing a field from a string with `FromStr`, for `from_env()` and
+//! `parse_args()`.
+//!
+//! A type without `FromStr` should be reported at the field with a message
+//! saying how to leave it out, rather than with rustc's list of the types
+//! that do implement `FromStr`. So the value is parsed through a method
+//! found by autoref: the one for `FromStr` types is tried first, and the
+//! fallback for every other type requires a trait that nothing implements,
+//! which carries the message.
+
+use proc_macro2::TokenStream;
+use quote::{quote, quote_spanned};
+use syn::spanned::Spanned;
+use syn::Type;
+
+/// The items `parse` calls, local to the function using it. `message` and
+/// `note` describe a field whose type does not implement `FromStr`.
+pub fn helpers(message: &str, note: &str) -> TokenStream {
+    let label = "the trait `FromStr` is not implemented for `{Self}`";
+    quote! {
+        #[diagnostic::on_unimplemented(message = #message, label = #label, note = #note)]
+        trait __NotFromStr {}
+
+        struct __Parse<__T>(::core::marker::PhantomData<__T>);
+
+        trait __ParseFromStr<__T: ::core::str::FromStr> {
+            fn __parse(&self, s: &str) -> ::core::result::Result<__T, __T::Err>;
+        }
+
+        impl<__T: ::core::str::FromStr> __ParseFromStr<__T> for &__Parse<__T> {
+            fn __parse(&self, s: &str) -> ::core::result::Result<__T, __T::Err> {
+                <__T as ::core::str::FromStr>::from_str(s)
+            }
+        }
+
+        trait __ParseOther<__T> {
+            fn __parse(&self, s: &str) -> ::core::result::Result<__T, ::core::convert::Infallible>
+            where
+                __T: __NotFromStr;
+        }
+
+        impl<__T> __ParseOther<__T> for __Parse<__T> {
+            fn __parse(&self, _: &str) -> ::core::result::Result<__T, ::core::convert::Infallible>
+            where
+                __T: __NotFromStr,
+            {
+                ::core::unreachable!()
+            }
+        }
+    }
+}
+
+/// `input` parsed as `ty`, a `Result` whose error is `Display`. A type
+/// without `FromStr` is reported at `ty`.
+pub fn parse(ty: &Type, input: TokenStream) -> TokenStream {
+    quote_spanned! {ty.span()=>
+        (&&__Parse::<#ty>(::core::marker::PhantomData)).__parse(#input)
+    }
+}
@@ -0,0 +1,306 @@
+//! Setter methods, described independently of their receiver so that each
+//! builder flavour decides whether they take `&mut self` or `self`.
+
+use crate::attr::Pattern;
+use crate::field::{Field, Kind};
+use crate::ty::Item;
+use proc_macro2::{Group, Span, TokenStream, TokenTree};
+use quote::{format_ident, quote, ToTokens};
+use syn::ext::IdentExt;
+use syn::{Ident, Visibility};
+
+pub struct Setter {
+    pub name: Ident,
+    /// Generic parameters of the method, e.g. `__V: Into<T>`.
+    pub generics: Vec<TokenStream>,
+    /// The method's arguments after the receiver, e.g. `value: __V`.
+    pub args: TokenStream,
+    /// The names of `args`, for forwarding them to the setter.
+    pub arg_names: Vec<Ident>,
+    /// Empty, or a `where` clause for the method.
+    pub where_clause: TokenStream,
+    /// Statements storing the arguments into the builder named by `target`.
+    pub body: TokenStream,
+}
+
+/// How the setters of a builder with a given pattern take and return it.
+pub struct Receiver {
+    pub receiver: TokenStream,
+    pub ret: TokenStream,
+    /// The builder the setter modifies and returns: `self`, or a copy of it.
+    pub target: TokenStream,
+    /// For immutable setters, the statement making the copy.
+    pub copy: Option<TokenStream>,
+}
+
+pub fn receiver(pattern: Pattern) -> Receiver {
+    let (receiver, ret, target) = match pattern {
+        Pattern::Mutable => (quote!(&mut self), quote!(&mut Self), quote!(self)),
+        Pattern::Owned => (quote!(mut self), quote!(Self), quote!(self)),
+        Pattern::Immutable => (quote!(&self), quote!(Self), quote!(__builder)),
+    };
+    let copy = (pattern == Pattern::Immutable).then(|| {
+        quote! {
+            let mut __builder = ::core::clone::Clone::clone(self);
+        }
+    });
+    Receiver {
+        receiver,
+        ret,
+        target,
+        copy,
+    }
+}
+
+/// `tokens` with every span set to `span`. A setter spanned at its field is
+/// reported there by rustc, e.g. when two setters have the same name, rather
+/// than at the `derive`.
+pub fn respan(tokens: TokenStream, span: Span) -> TokenStream {
+    tokens
+        .into_iter()
+        .map(|mut tt| {
+            if let TokenTree::Group(group) = &tt {
+                let mut respanned = Group::new(group.delimiter(), respan(group.stream(), span));
+                respanned.set_span(span);
+                tt = TokenTree::Group(respanned);
+            } else {
+                tt.set_span(span);
+            }
+            tt
+        })
+        .collect()
+}
+
+/// How a setter takes an argument for a `ty`, and the expression converting
+/// the argument `value` into one.
+pub struct Param {
+    pub generics: Option<TokenStream>,
+    pub arg_ty: TokenStream,
+    pub value: TokenStream,
+}
+
+pub fn param(ty: impl ToTokens, into: bool) -> Param {
+    named_param(ty, into, &format_ident!("value"), &format_ident!("__V"))
+}
+
+pub fn named_param(ty: impl ToTokens, into: bool, arg: &Ident, generic: &Ident) -> Param {
+    if into {
+        Param {
+            generics: Some(quote!(#generic: ::core::convert::Into<#ty>)),
+            arg_ty: quote!(#generic),
+            value: quote!(::core::convert::Into::into(#arg)),
+        }
+    } else {
+        Param {
+            generics: None,
+            arg_ty: quote!(#ty),
+            value: quote!(#arg),
+        }
+    }
+}
+
+/// The setters for `field`, storing into `target`, which is either `self` or
+/// a local holding the builder being modified.
+pub fn setters(field: &Field, target: &TokenStream) -> Vec<Setter> {
+    let opts = &field.setter;
+    if opts.skip || matches!(field.kind, Kind::Sub(_)) {
+        return Vec::new();
+    }
+
+    let ident = &field.ident;
+    let ty = &field.ty;
+
+    let whole = match &field.kind {
+        Kind::Optional(inner) if opts.strip_option => {
+            let Param {
+                generics,
+                arg_ty,
+                value,
+            } = param(inner, opts.into);
+            Setter {
+                name: opts.name.clone(),
+                generics: generics.into_iter().collect(),
+                args: quote!(value: #arg_ty),
+                arg_names: vec![format_ident!("value")],
+                where_clause: TokenStream::new(),
+                body: quote! {
+                    #target.#ident = ::core::option::Option::Some(
+                        ::core::option::Option::Some(#value),
+                    );
+                },
+            }
+        }
+        _ => {
+            let Param {
+                generics,
+                arg_ty,
+                value,
+            } = param(ty, opts.into);
+            Setter {
+                name: opts.name.clone(),
+                generics: generics.into_iter().collect(),
+                args: quote!(value: #arg_ty),
+                arg_names: vec![format_ident!("value")],
+                where_clause: TokenStream::new(),
+                body: quote! {
+                    #target.#ident = ::core::option::Option::Some(#value);
+                },
+            }
+        }
+    };
+
+    let Kind::Each { setter: each, item } = &field.kind else {
+        return vec![whole];
+    };
+
+    let collection = quote! {
+        #target.#ident.get_or_insert_with(::core::default::Default::default)
+    };
+
+    let each = match item {
+        Item::Single(item) => {
+            let Param {
+                generics,
+                arg_ty,
+                value,
+            } = param(item, opts.into);
+            Setter {
+                name: each.clone(),
+                generics: generics.into_iter().collect(),
+                args: quote!(value: #arg_ty),
+                arg_names: vec![format_ident!("value")],
+                where_clause: TokenStream::new(),
+                body: quote! {
+                    ::core::iter::Extend::extend(#collection, ::core::iter::once(#value));
+                },
+            }
+        }
+        Item::Pair(key_ty, value_ty) => {
+            let key = named_param(
+                key_ty,
+                opts.into,
+                &format_ident!("key"),
+                &format_ident!("__K"),
+            );
+            let value = named_param(
+                value_ty,
+                opts.into,
+                &format_ident!("value"),
+                &format_ident!("__V"),
+            );
+            let (key_generics, key_arg, key_value) = (key.generics, key.arg_ty, key.value);
+            let (value_generics, value_arg, value_value) =
+                (value.generics, value.arg_ty, value.value);
+            Setter {
+                name: each.clone(),
+                generics: key_generics.into_iter().chain(value_generics).collect(),
+                args: quote!(key: #key_arg, value: #value_arg),
+                arg_names: vec![format_ident!("key"), format_ident!("value")],
+                where_clause: TokenStream::new(),
+                body: quote! {
+                    ::core::iter::Extend::extend(
+                        #collection,
+                        ::core::iter::once((#key_value, #value_value)),
+                    );
+                },
+            }
+        }
+        // Without knowing the item type there is nothing to convert into, so
+        // the setter accepts whatever the collection can be extended with.
+        Item::Unknown => Setter {
+            name: each.clone(),
+            generics: vec![quote!(__T)],
+            args: quote!(value: __T),
+            arg_names: vec![format_ident!("value")],
+            where_clause: quote!(where #ty: ::core::iter::Extend<__T>),
+            body: quote! {
+                ::core::iter::Extend::extend(#collection, ::core::iter::once(value));
+            },
+        },
+    };
+
+    let extend = Setter {
+        name: format_ident!("extend_{}", ident.unraw()),
+        generics: vec![quote!(__I: ::core::iter::IntoIterator)],
+        args: quote!(iter: __I),
+        arg_names: vec![format_ident!("iter")],
+        where_clause: quote!(where #ty: ::core::iter::Extend<__I::Item>),
+        body: quote! {
+            ::core::iter::Extend::extend(#collection, iter);
+        },
+    };
+
+    // A per-item setter with the same name as the setter for the whole
+    // collection replaces it.
+    if each.name == whole.name {
+        vec![each, extend]
+    } else {
+        vec![whole, each, extend]
+    }
+}
+
+/// For a `sub_builder` field, a method giving access to the nested builder,
+/// which is created on first use. It borrows the builder mutably whichever
+/// pattern the setters use.
+pub fn sub_builder_accessor(vis: &Visibility, field: &Field) -> Option<TokenStream> {
+    let Kind::Sub(sub) = &field.kind else {
+        return None;
+    };
+    // A flattened builder is reached through its forwarded setters instead.
+    if field.setter.skip || sub.flatten.is_some() {
+        return Some(TokenStream::new());
+    }
+
+    let name = &field.setter.name;
+    let ident = &field.ident;
+    let target = &sub.target;
+    let builder = &sub.builder;
+    let docs = &field.docs;
+    Some(quote! {
+        #(#docs)*
+        #vis fn #name(&mut self) -> &mut #builder {
+            self.#ident.get_or_insert_with(<#target>::builder)
+        }
+    })
+}
+
+/// The name of the `try_` setter for a field with `builder(try_setter)`,
+/// and the type its argument is converted into with `TryInto` before being
+/// passed on to the regular setter.
+pub fn try_setter_target(field: &Field) -> Option<(Ident, TokenStream)> {
+    field.try_setter?;
+    let name = format_ident!("try_{}", field.setter.name.unraw());
+    let ty = match &field.kind {
+        Kind::Optional(inner) if field.setter.strip_option => quote!(#inner),
+        _ => {
+            let ty = &field.ty;
+            quote!(#ty)
+        }
+    };
+    Some((name, ty))
+}
+
+/// A `try_` setter taking the builder as `receiver`, which returns the
+/// conversion error, or else whatever the regular setter returns.
+pub fn fallible_try_setter(
+    vis: &Visibility,
+    field: &Field,
+    receiver: &TokenStream,
+    ret: &TokenStream,
+) -> Option<TokenStream> {
+    let (name, ty) = try_setter_target(field)?;
+    let setter = &field.setter.name;
+    let docs = &field.docs;
+    Some(quote! {
+        #(#docs)*
+        #vis fn #name<__V: ::core::convert::TryInto<#ty>>(
+            #receiver,
+            value: __V,
+        ) -> ::core::result::Result<#ret, <__V as ::core::convert::TryInto<#ty>>::Error> {
+            match ::core::convert::TryInto::try_into(value) {
+                ::core::result::Result::Ok(value) => ::core::result::Result::Ok(self.#setter(value)),
+                ::core::result::Result::Err(err) => ::core::result::Result::Err(err),
+            }
+        }
+    })
+}
@@ -0,0 +1,147 @@
+use crate::{expand, flatten};
+use proc_macro2::{TokenStream, TokenTree};
+use quote::ToTokens;
+use syn::{GenericArgument, Generics, Path, PathArguments, Type};
+
+/// Returns the type arguments if `ty` is written as `Wrapper<A, B, ...>`
+/// (optionally through a path such as `std::collections::HashMap<K, V>`).
+fn generic_args<'a>(ty: &'a Type, wrapper: &str) -> Option<Vec<&'a Type>> {
+    let Type::Path(path) = ty else {
+        return None;
+    };
+    if path.qself.is_some() {
+        return None;
+    }
+
+    let segment = path.path.segments.last()?;
+    if segment.ident != wrapper {
+        return None;
+    }
+
+    let PathArguments::AngleBracketed(args) = &segment.arguments else {
+        return None;
+    };
+    args.args
+        .iter()
+        .map(|arg| match arg {
+            GenericArgument::Type(ty) => Some(ty),
+            _ => None,
+        })
+        .collect()
+}
+
+pub fn option_inner(ty: &Type) -> Option<&Type> {
+    match generic_args(ty, "Option")?.as_slice() {
+        [inner] => Some(inner),
+        _ => None,
+    }
+}
+
+/// What a collection used with `builder(each = "...")` is extended with.
+pub enum Item {
+    /// `Vec<T>`, `VecDeque<T>`, the std sets and the like: one `T` per item.
+    Single(Box<Type>),
+    /// `HashMap<K, V>` and `BTreeMap<K, V>`: a key and a value per item.
+    Pair(Box<Type>, Box<Type>),
+    /// Any other `Default + Extend<_>` type, whose item type is inferred.
+    Unknown,
+}
+
+pub fn collection_item(ty: &Type) -> Item {
+    const SINGLE: &[&str] = &[
+        "Vec",
+        "VecDeque",
+        "LinkedList",
+        "BinaryHeap",
+        "HashSet",
+        "BTreeSet",
+    ];
+    const PAIR: &[&str] = &["HashMap", "BTreeMap"];
+
+    for wrapper in SINGLE {
+        // HashSet may carry a hasher as a second argument.
+        if let Some([item, ..]) = generic_args(ty, wrapper).as_deref() {
+            return Item::Single(Box::new((*item).clone()));
+        }
+    }
+    for wrapper in PAIR {
+        if let Some([key, value, ..]) = generic_args(ty, wrapper).as_deref() {
+            return Item::Pair(Box::new((*key).clone()), Box::new((*value).clone()));
+        }
+    }
+    Item::Unknown
+}
+
+/// The builder and error types that `derive(Builder)` generates for `ty`,
+/// through the aliases named after it: `path::__TlsConfigBuilder<T>` and
+/// `path::__TlsConfigBuilderError` for `path::TlsConfig<T>`.
+pub fn builder_of(ty: &Type) -> Option<(Type, Path)> {
+    let Type::Path(path) = ty else {
+        return None;
+    };
+    if path.qself.is_some() {
+        return None;
+    }
+
+    let mut builder = path.clone();
+    let segment = builder.path.segments.last_mut()?;
+    let (builder_alias, error_alias) = expand::alias_idents(&segment.ident);
+    segment.ident = builder_alias;
+
+    let mut error = builder.path.clone();
+    let segment = error.segments.last_mut()?;
+    segment.ident = error_alias;
+    segment.arguments = PathArguments::None;
+
+    Some((Type::Path(builder), error))
+}
+
+/// The macro `derive(Builder)` generates alongside the builder of `ty` for
+/// `builder(flatten)`, e.g. `path::__TlsConfigBuilderFlatten` for
+/// `path::TlsConfig`.
+pub fn flatten_macro_of(ty: &Type) -> Option<Path> {
+    let Type::Path(path) = ty else {
+        return None;
+    };
+    let mut path = path.path.clone();
+    let segment = path.segments.last_mut()?;
+    segment.ident = flatten::macro_ident(&segment.ident);
+    segment.arguments = PathArguments::None;
+    Some(path)
+}
+
+/// Whether `ty` refers to any of the type parameters declared in `generics`.
+pub fn mentions_type_param(ty: &Type, generics: &Generics) -> bool {
+    fn visit(tokens: TokenStream, generics: &Generics) -> bool {
+        tokens.into_iter().any(|tt| match tt {
+            TokenTree::Ident(ident) => generics.type_params().any(|param| param.ident == ident),
+            TokenTree::Group(group) => visit(group.stream(), generics),
+            _ => false,
+        })
+    }
+    visit(ty.to_token_stream(), generics)
+}
+
+/// `ty` as it would be written in source, e.g. `Vec<String>` rather than the
+/// `Vec < String >` of its token stream.
+pub fn display(ty: &Type) -> String {
+    const JOINS: &[(&str, &str)] = &[
+        (" :: ", "::"),
+        (":: ", "::"),
+        (" <", "<"),
+        ("< ", "<"),
+        (" >", ">"),
+        (" ,", ","),
+        ("& ", "&"),
+        ("( ", "("),
+        (" )", ")"),
+        ("[ ", "["),
+        (" ]", "]"),
+        (" ;", ";"),
+    ];
+    let mut text = ty.to_token_stream().to_string();
+    for (from, to) in JOINS {
+        text = text.replace(from, to);
+    }
+    text
+}
@@ -0,0 +1,494 @@
+//! `#[builder(typestate)]`: the builder carries one type parameter per
+//! required field, `()` while the field is unset and `(T,)` once it is set, so
+//! that `build()` only type-checks after every required field was provided.
+
+use crate::expand::Context;
+use crate::field::{DefaultValue, Field, Kind};
+use crate::order::Binding;
+use crate::setter::{self, Param, Setter};
+use crate::{cli, convert, env, flatten, introspect};
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::ext::IdentExt;
+use syn::{parse_quote, Error, GenericParam, Generics, Ident, Result};
+
+struct State<'a> {
+    field: &'a Field,
+    param: Ident,
+    is_set: Ident,
+}
+
+pub fn expand(cx: &Context) -> Result<TokenStream> {
+    if cx.variant.is_some() {
+        return Err(Error::new_spanned(
+            cx.ident,
+            "`builder(typestate)` is not supported on enums",
+        ));
+    }
+
+    // Typestate setters always consume the builder, since each required
+    // field changes its type.
+    if let Some((_, span)) = cx.attrs.pattern {
+        return Err(Error::new(
+            span,
+            "`builder(pattern = \"...\")` is not supported with `builder(typestate)`",
+        ));
+    }
+
+    // A typestate build() cannot fail, so there is nowhere to report a
+    // validation error.
+    if let Some(validate) = &cx.attrs.build_fn.validate {
+        return Err(Error::new_spanned(
+            validate,
+            "`build_fn(validate = \"...\")` is not supported with `builder(typestate)`",
+        ));
+    }
+
+    if let Some(error) = &cx.attrs.build_fn.error {
+        return Err(Error::new_spanned(
+            error,
+            "`build_fn(error = \"...\")` is not supported with `builder(typestate)`",
+        ));
+    }
+
+    if let Some((field, sub)) = cx.fields.iter().find_map(|f| match &f.kind {
+        Kind::Sub(sub) => Some((f, sub)),
+        _ => None,
+    }) {
+        return Err(Error::new_spanned(
+            &field.ident,
+            format!(
+                "`builder({})` is not supported with `builder(typestate)`",
+                sub.attr(),
+            ),
+        ));
+    }
+
+    if cx.attrs.serde {
+        return Err(Error::new_spanned(
+            cx.ident,
+            "`builder(serde)` is not supported with `builder(typestate)`",
+        ));
+    }
+
+    if let Some(span) = cx.attrs.positional {
+        return Err(Error::new(
+            span,
+            "`builder(positional)` is not supported with `builder(typestate)`",
+        ));
+    }
+
+    if env::is_used(cx) {
+        return Err(Error::new_spanned(
+            cx.ident,
+            "`builder(env = \"...\")` and `builder(env_prefix = \"...\")` are not supported \
+             with `builder(typestate)`",
+        ));
+    }
+
+    if let Some((_, span)) = cx.fields.iter().find_map(|field| field.merge) {
+        return Err(Error::new(
+            span,
+            "`builder(merge = \"...\")` is not supported with `builder(typestate)`",
+        ));
+    }
+
+    // The closure would be written against a builder type that typestate
+    // builders do not have.
+    if let Some(field) =
+        (cx.fields.iter()).find(|f| matches!(f.default, Some(DefaultValue::With(_))))
+    {
+        return Err(Error::new_spanned(
+            &field.ident,
+            "`builder(default_with = \"...\")` is not supported with `builder(typestate)`",
+        ));
+    }
+
+    if cli::is_used(cx) {
+        return Err(Error::new_spanned(
+            cx.ident,
+            "`builder(cli)` is not supported with `builder(typestate)`",
+        ));
+    }
+
+    if cx.defers_errors() {
+        return Err(Error::new_spanned(
+            cx.ident,
+            "`builder(try_setter(deferred))` is not supported with `builder(typestate)`",
+        ));
+    }
+
+    let states: Vec<State> = cx
+        .fields
+        .iter()
+        .filter(|field| field.is_required())
+        .map(|field| State {
+            field,
+            param: format_ident!("__{}", field.camel()),
+            is_set: format_ident!("{}Has{}", cx.builder_ident, field.camel()),
+        })
+        .collect();
+
+    let builder_struct = builder_struct(cx, &states);
+    let is_set_traits = states.iter().map(|state| is_set_trait(cx, state));
+    let builder_fn = builder_fn(cx);
+    let setters = cx.fields.iter().map(|field| setters(cx, &states, field));
+    let build_fn = build_fn(cx, &states);
+    let complete_ty = builder_ty(cx, &states, |state| {
+        let ty = &state.field.ty;
+        quote!((#ty,))
+    });
+    let convert = convert::expand(cx, &complete_ty, |field| state_of(&states, field).is_some());
+    let flatten_macro = flatten::define(cx);
+
+    let mut generics = cx.generics.clone();
+    for state in &states {
+        let param = &state.param;
+        generics.params.push(parse_quote!(#param));
+    }
+    let (impl_generics, _, where_clause) = generics.split_for_impl();
+    let self_ty = builder_ty(cx, &states, |state| {
+        let param = &state.param;
+        quote!(#param)
+    });
+    let state_trait = state_trait(cx);
+    let introspect = introspect(cx, &states, &generics, &self_ty);
+
+    Ok(quote! {
+        #builder_struct
+        #(#is_set_traits)*
+        #builder_fn
+
+        impl #impl_generics #self_ty #where_clause {
+            #(#setters)*
+            #build_fn
+        }
+
+        #state_trait
+        #introspect
+        #convert
+        #flatten_macro
+    })
+}
+
+fn state_trait_ident(cx: &Context) -> Ident {
+    format_ident!("{}State", cx.builder_ident)
+}
+
+/// A trait implemented by both states of a required field, telling at run
+/// time which one the builder is in.
+fn state_trait(cx: &Context) -> TokenStream {
+    let vis = &cx.vis;
+    let state = state_trait_ident(cx);
+    quote! {
+        #[doc(hidden)]
+        #vis trait #state {
+            const IS_SET: ::core::primitive::bool;
+        }
+
+        impl #state for () {
+            const IS_SET: ::core::primitive::bool = false;
+        }
+
+        impl<T> #state for (T,) {
+            const IS_SET: ::core::primitive::bool = true;
+        }
+    }
+}
+
+/// The introspection methods, available in every state of the builder, and
+/// `FIELDS`.
+fn introspect(
+    cx: &Context,
+    states: &[State],
+    generics: &Generics,
+    self_ty: &TokenStream,
+) -> TokenStream {
+    let state_trait = state_trait_ident(cx);
+    let mut generics = generics.clone();
+    let where_clause = generics.make_where_clause();
+    for state in states {
+        let param = &state.param;
+        where_clause
+            .predicates
+            .push(parse_quote!(#param: #state_trait));
+    }
+    let (impl_generics, _, where_clause) = generics.split_for_impl();
+    // In the initial state, so that `FIELDS` needs no state to be named.
+    let (struct_impl_generics, _, struct_where_clause) = cx.generics.split_for_impl();
+    let initial_ty = builder_ty(cx, states, |_| quote!(()));
+    introspect::expand(
+        cx,
+        quote!(impl #struct_impl_generics #initial_ty #struct_where_clause),
+        quote!(impl #impl_generics #self_ty #where_clause),
+        |field| match state_of(states, field) {
+            Some(state) => {
+                let param = &state.param;
+                quote!(<#param as #state_trait>::IS_SET)
+            }
+            None => {
+                let ident = &field.ident;
+                quote!(self.#ident.is_some())
+            }
+        },
+    )
+}
+
+fn state_of<'a>(states: &'a [State], field: &Field) -> Option<&'a State<'a>> {
+    states.iter().find(|state| state.field.ident == field.ident)
+}
+
+/// The builder type with the struct's own generic arguments followed by one
+/// argument per required field, as chosen by `state_arg`.
+fn builder_ty(
+    cx: &Context,
+    states: &[State],
+    state_arg: impl Fn(&State) -> TokenStream,
+) -> TokenStream {
+    let builder_ident = &cx.builder_ident;
+    let args = cx.generics.params.iter().map(|param| match param {
+        GenericParam::Lifetime(param) => {
+            let lifetime = &param.lifetime;
+            quote!(#lifetime)
+        }
+        GenericParam::Type(param) => {
+            let ident = &param.ident;
+            quote!(#ident)
+        }
+        GenericParam::Const(param) => {
+            let ident = &param.ident;
+            quote!(#ident)
+        }
+    });
+    let states = states.iter().map(state_arg);
+    quote!(#builder_ident<#(#args,)* #(#states),*>)
+}
+
+fn builder_struct(cx: &Context, states: &[State]) -> TokenStream {
+    let vis = &cx.vis;
+    let builder_ident = &cx.builder_ident;
+    let mut generics = cx.generics.clone();
+    for state in states {
+        let param = &state.param;
+        generics.params.push(parse_quote!(#param = ()));
+    }
+    let where_clause = &generics.where_clause;
+    let phantom = cx.phantom().map(|(field, _)| quote!(#field,));
+    let doc = format!(
+        "Builder for [`{}`]. Each type parameter tracks whether one required \
+         field has been set; `build()` is only available once all of them are.",
+        cx.ident,
+    );
+
+    let fields = cx.fields.iter().map(|field| {
+        let field_vis = &field.vis;
+        let ident = &field.ident;
+        let ty = &field.ty;
+        match state_of(states, field) {
+            Some(state) => {
+                let param = &state.param;
+                quote!(#field_vis #ident: #param,)
+            }
+            None => quote!(#field_vis #ident: ::core::option::Option<#ty>,),
+        }
+    });
+    let derives = cx.derives();
+
+    quote! {
+        #[doc = #doc]
+        #derives
+        #vis struct #builder_ident #generics #where_clause {
+            #(#fields)*
+            #phantom
+        }
+    }
+}
+
+/// A trait implemented only by the "set" state `(T,)`, carrying the
+/// diagnostic shown when `build()` is called with the field still unset.
+fn is_set_trait(cx: &Context, state: &State) -> TokenStream {
+    let vis = &cx.vis;
+    let is_set = &state.is_set;
+    let name = state.field.name();
+    let build_fn = cx.build_fn_name();
+    let message = format!(
+        "`{}::{}` requires the field `{}` to be set",
+        cx.builder_ident, build_fn, name,
+    );
+    let label = format!("`{}` has not been set", name);
+    let note = format!(
+        "call `.{}(...)` before `.{}()`",
+        state.field.setter.name.unraw(),
+        build_fn,
+    );
+
+    quote! {
+        #[doc(hidden)]
+        #[diagnostic::on_unimplemented(message = #message, label = #label, note = #note)]
+        #vis trait #is_set<T> {
+            fn into_value(self) -> T;
+        }
+
+        impl<T> #is_set<T> for (T,) {
+            fn into_value(self) -> T {
+                self.0
+            }
+        }
+    }
+}
+
+fn builder_fn(cx: &Context) -> TokenStream {
+    let vis = &cx.vis;
+    let ident = cx.ident;
+    let builder_ident = &cx.builder_ident;
+    let doc = format!("Returns a [`{}`] with no fields set.", builder_ident);
+
+    let inits = cx.fields.iter().map(|field| {
+        let ident = &field.ident;
+        if field.is_required() {
+            quote!(#ident: ())
+        } else {
+            quote!(#ident: ::core::option::Option::None)
+        }
+    });
+    let phantom = cx.phantom().map(|(_, init)| quote!(#init,));
+    let (impl_generics, ty_generics, where_clause) = cx.generics.split_for_impl();
+
+    quote! {
+        impl #impl_generics #ident #ty_generics #where_clause {
+            #[doc = #doc]
+            #vis fn builder() -> #builder_ident #ty_generics {
+                #builder_ident {
+                    #(#inits,)*
+                    #phantom
+                }
+            }
+        }
+    }
+}
+
+fn setters(cx: &Context, states: &[State], field: &Field) -> TokenStream {
+    let vis = &cx.vis;
+    let builder_ident = &cx.builder_ident;
+    let ident = &field.ident;
+    let ty = &field.ty;
+    let docs = &field.docs;
+
+    // Setting a required field moves every other field across into a
+    // builder whose state for this field is `(T,)`.
+    if field.is_required() {
+        let name = &field.setter.name;
+        let Param {
+            generics,
+            arg_ty,
+            value,
+        } = setter::param(ty, field.setter.into);
+        let return_ty = builder_ty(cx, states, |state| {
+            if state.field.ident == *ident {
+                quote!((#ty,))
+            } else {
+                let param = &state.param;
+                quote!(#param)
+            }
+        });
+        let try_setter = setter::fallible_try_setter(vis, field, &quote!(self), &return_ty);
+        let phantom = cx.phantom().map(|_| quote!(__phantom: self.__phantom,));
+        let moves = cx.fields.iter().map(|other| {
+            let other = &other.ident;
+            if other == ident {
+                quote!(#other: (#value,))
+            } else {
+                quote!(#other: self.#other)
+            }
+        });
+        return quote! {
+            #(#docs)*
+            #vis fn #name<#generics>(self, value: #arg_ty) -> #return_ty {
+                #builder_ident {
+                    #(#moves,)*
+                    #phantom
+                }
+            }
+
+            #try_setter
+        };
+    }
+
+    let setters = setter::setters(field, &quote!(self))
+        .into_iter()
+        .map(|setter| {
+            let Setter {
+                name,
+                generics,
+                args,
+                where_clause,
+                body,
+                ..
+            } = setter;
+            quote! {
+                #(#docs)*
+                #vis fn #name<#(#generics),*>(mut self, #args) -> Self #where_clause {
+                    #body
+                    self
+                }
+            }
+        });
+    let try_setter = setter::fallible_try_setter(vis, field, &quote!(self), &quote!(Self));
+    quote!(#(#setters)* #try_setter)
+}
+
+fn build_fn(cx: &Context, states: &[State]) -> TokenStream {
+    let ident = cx.ident;
+    let (_, ty_generics, _) = cx.generics.split_for_impl();
+
+    let bounds = states.iter().map(|state| {
+        let param = &state.param;
+        let is_set = &state.is_set;
+        let ty = &state.field.ty;
+        quote!(#param: #is_set<#ty>)
+    });
+    let struct_default = cx.struct_default();
+    let bindings = cx.build_order().map(|binding| {
+        let field = match binding {
+            Binding::Field(field) => field,
+            Binding::Computed(computed) => return computed.binding(),
+        };
+        let field_ident = &field.ident;
+        match state_of(states, field) {
+            Some(state) => {
+                let param = &state.param;
+                let is_set = &state.is_set;
+                let ty = &field.ty;
+                quote! {
+                    let #field_ident = <#param as #is_set<#ty>>::into_value(self.#field_ident);
+                }
+            }
+            None => {
+                let unset = cx.unset_value(field);
+                quote! {
+                    let #field_ident = match self.#field_ident {
+                        ::core::option::Option::Some(value) => value,
+                        ::core::option::Option::None => #unset,
+                    };
+                }
+            }
+        }
+    });
+    let built = cx.post_build(cx.construct());
+
+    let vis = cx.build_fn_vis();
+    let name = cx.build_fn_name();
+    let doc = format!("Builds the [`{}`].", ident);
+
+    quote! {
+        #[doc = #doc]
+        #vis fn #name(self) -> #ident #ty_generics
+        where
+            #(#bounds,)*
+        {
+            #struct_default
+            #(#bindings)*
+            #built
+        }
+    }
+}
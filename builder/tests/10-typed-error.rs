@@ -0,0 +1,39 @@
+// Instead of a boxed error, build() returns a `<Name>BuilderError` enum
+// generated alongside the builder. Each required field gets its own
+// `Missing*` variant so callers can match on exactly what was left unset.
+
+use derive_builder::Builder;
+use std::error::Error;
+
+#[derive(Builder, Debug)]
+pub struct Command {
+    executable: String,
+    #[builder(each = "arg")]
+    args: Vec<String>,
+    current_dir: Option<String>,
+    work_dir: String,
+}
+
+fn main() {
+    let err = Command::builder().build().unwrap_err();
+    assert_eq!(err, CommandBuilderError::MissingExecutable);
+    assert_eq!(err.to_string(), "missing required field `executable`");
+
+    let err = Command::builder()
+        .executable("cargo".to_owned())
+        .build()
+        .unwrap_err();
+    assert_eq!(err, CommandBuilderError::MissingWorkDir);
+
+    // The error still converts into the usual boxed trait object.
+    let boxed: Box<dyn Error> = Box::new(err.clone());
+    assert_eq!(boxed.to_string(), "missing required field `work_dir`");
+
+    let command = Command::builder()
+        .executable("cargo".to_owned())
+        .work_dir("..".to_owned())
+        .build()
+        .unwrap();
+    assert!(command.args.is_empty());
+    assert!(command.current_dir.is_none());
+}
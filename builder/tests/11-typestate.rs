@@ -0,0 +1,39 @@
+// With #[builder(typestate)] the builder records in its type which required
+// fields have been set, so build() returns the struct directly instead of a
+// Result. Option and `each` fields stay optional and may be set in any order.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+#[builder(typestate)]
+pub struct Command {
+    executable: String,
+    #[builder(each = "arg")]
+    args: Vec<String>,
+    env: Vec<String>,
+    current_dir: Option<String>,
+}
+
+fn main() {
+    let command = Command::builder()
+        .arg("build".to_owned())
+        .env(vec![])
+        .executable("cargo".to_owned())
+        .arg("--release".to_owned())
+        .build();
+
+    assert_eq!(command.executable, "cargo");
+    assert_eq!(command.args, vec!["build", "--release"]);
+    assert!(command.current_dir.is_none());
+
+    // Setting a required field again replaces the earlier value.
+    let command = Command::builder()
+        .env(vec!["RUST_LOG=debug".to_owned()])
+        .executable("rustc".to_owned())
+        .executable("cargo".to_owned())
+        .current_dir("..".to_owned())
+        .build();
+
+    assert_eq!(command.executable, "cargo");
+    assert_eq!(command.current_dir.as_deref(), Some(".."));
+}
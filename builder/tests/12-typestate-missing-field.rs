@@ -0,0 +1,19 @@
+// Forgetting a required field in typestate mode is a compile error that names
+// the field, rather than an error returned from build() at runtime.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+#[builder(typestate)]
+pub struct Command {
+    executable: String,
+    env: Vec<String>,
+    current_dir: Option<String>,
+}
+
+fn main() {
+    let _command = Command::builder()
+        .env(vec![])
+        .current_dir("..".to_owned())
+        .build();
+}
@@ -0,0 +1,74 @@
+// Fields marked #[builder(default)] fall back to Default::default() and fields
+// with #[builder(default = "...")] to an arbitrary expression, which may refer
+// to the struct's other fields by name. A struct-level #[builder(default)]
+// takes every unset field from the struct's own Default impl instead.
+
+use derive_builder::Builder;
+
+#[derive(Builder, Debug)]
+pub struct Command {
+    executable: String,
+    #[builder(default)]
+    args: Vec<String>,
+    #[builder(default = "format!(\"{}.log\", executable)")]
+    log_file: String,
+    #[builder(default = "1")]
+    jobs: usize,
+}
+
+#[derive(Builder)]
+#[builder(default)]
+pub struct Limits {
+    max_connections: u32,
+    timeout_secs: u64,
+    name: Option<String>,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Limits {
+            max_connections: 64,
+            timeout_secs: 30,
+            name: Some("default".to_owned()),
+        }
+    }
+}
+
+#[derive(Builder)]
+#[builder(typestate)]
+pub struct Job {
+    name: String,
+    #[builder(default = "name.len()")]
+    priority: usize,
+}
+
+fn main() {
+    let command = Command::builder()
+        .executable("cargo".to_owned())
+        .build()
+        .unwrap();
+    assert!(command.args.is_empty());
+    assert_eq!(command.log_file, "cargo.log");
+    assert_eq!(command.jobs, 1);
+
+    let command = Command::builder()
+        .executable("cargo".to_owned())
+        .log_file("build.log".to_owned())
+        .jobs(8)
+        .build()
+        .unwrap();
+    assert_eq!(command.log_file, "build.log");
+    assert_eq!(command.jobs, 8);
+
+    // Defaulted fields are not required.
+    let err = Command::builder().build().unwrap_err();
+    assert_eq!(err, CommandBuilderError::MissingExecutable);
+
+    let limits = Limits::builder().timeout_secs(5).build().unwrap();
+    assert_eq!(limits.max_connections, 64);
+    assert_eq!(limits.timeout_secs, 5);
+    assert_eq!(limits.name.as_deref(), Some("default"));
+
+    let job = Job::builder().name("deploy".to_owned()).build();
+    assert_eq!(job.priority, 6);
+}
@@ -0,0 +1,76 @@
+// #[builder(build_fn(validate = "..."))] names a function that build() calls
+// with the builder once all required fields are known to be set. Returning Err
+// aborts the build with the error's message in the Validation variant.
+
+use derive_builder::Builder;
+use std::fmt::{self, Display};
+
+#[derive(Builder, Debug)]
+#[builder(build_fn(validate = "validate_command"))]
+pub struct Command {
+    executable: String,
+    current_dir: Option<String>,
+}
+
+fn validate_command(builder: &CommandBuilder) -> Result<(), String> {
+    match (&builder.executable, &builder.current_dir) {
+        (Some(executable), Some(Some(_))) if executable.is_empty() => {
+            Err("`current_dir` is set but `executable` is empty".to_owned())
+        }
+        _ => Ok(()),
+    }
+}
+
+mod checks {
+    use super::*;
+
+    pub struct PortError(pub u16);
+
+    impl Display for PortError {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "port {} is reserved", self.0)
+        }
+    }
+
+    pub fn port(builder: &ServerBuilder) -> Result<(), PortError> {
+        match builder.port {
+            Some(port) if port < 1024 => Err(PortError(port)),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[derive(Builder, Debug)]
+#[builder(build_fn(validate = "checks::port"))]
+pub struct Server {
+    port: u16,
+}
+
+fn main() {
+    let err = Command::builder()
+        .executable(String::new())
+        .current_dir("..".to_owned())
+        .build()
+        .unwrap_err();
+    assert_eq!(
+        err,
+        CommandBuilderError::Validation(
+            "`current_dir` is set but `executable` is empty".to_owned()
+        ),
+    );
+
+    // Missing required fields are reported before validation runs.
+    let err = Command::builder().build().unwrap_err();
+    assert_eq!(err, CommandBuilderError::MissingExecutable);
+
+    let command = Command::builder()
+        .executable("cargo".to_owned())
+        .current_dir("..".to_owned())
+        .build()
+        .unwrap();
+    assert_eq!(command.executable, "cargo");
+
+    let err = Server::builder().port(80).build().unwrap_err();
+    assert_eq!(err.to_string(), "port 80 is reserved");
+    assert_eq!(Server::builder().port(8080).build().unwrap().port, 8080);
+}
@@ -0,0 +1,45 @@
+// The builder carries the struct's type parameters, including their bounds.
+// Option<T> and Vec<T> are still recognized when T is one of the parameters.
+
+use derive_builder::Builder;
+use std::fmt::Display;
+use std::marker::PhantomData;
+
+#[derive(Builder)]
+pub struct Command<T: Display + Clone, U> {
+    executable: T,
+    #[builder(each = "arg")]
+    args: Vec<T>,
+    env: Vec<U>,
+    current_dir: Option<U>,
+}
+
+#[derive(Builder)]
+pub struct Tagged<T> {
+    id: u32,
+    #[builder(default)]
+    tag: PhantomData<T>,
+}
+
+fn main() {
+    let command = Command::builder()
+        .executable("cargo")
+        .arg("build")
+        .arg("--release")
+        .env(vec![1, 2])
+        .build()
+        .unwrap();
+    assert_eq!(command.executable, "cargo");
+    assert_eq!(command.args, vec!["build", "--release"]);
+    assert!(command.current_dir.is_none());
+
+    let builder: CommandBuilder<String, u8> = Command::builder();
+    let err = builder.build().err().unwrap();
+    assert_eq!(err, CommandBuilderError::MissingExecutable);
+
+    // build() clones field values, but only requires the field types to be
+    // Clone rather than T itself.
+    struct NotClone;
+    let tagged: Tagged<NotClone> = Tagged::builder().id(7).build().unwrap();
+    assert_eq!(tagged.id, 7);
+}
@@ -0,0 +1,28 @@
+// Lifetime parameters are carried through to the builder, so borrowed fields
+// can be set without allocating.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+pub struct Command<'a, 'b: 'a> {
+    executable: &'a str,
+    #[builder(each = "arg")]
+    args: Vec<&'b str>,
+    current_dir: Option<&'a str>,
+}
+
+fn main() {
+    let executable = String::from("cargo");
+    let args = [String::from("build"), String::from("--release")];
+
+    let mut builder = Command::builder();
+    builder.executable(&executable);
+    for arg in &args {
+        builder.arg(arg);
+    }
+    let command = builder.build().unwrap();
+
+    assert_eq!(command.executable, "cargo");
+    assert_eq!(command.args, ["build", "--release"]);
+    assert_eq!(command.current_dir, None);
+}
@@ -0,0 +1,19 @@
+// Const generic parameters are carried through to the builder as well.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+pub struct Buffer<const N: usize, T: Copy = u8> {
+    data: [T; N],
+    label: Option<&'static str>,
+}
+
+fn main() {
+    let buffer = Buffer::builder().data([1, 2, 3, 4]).build().unwrap();
+    assert_eq!(buffer.data.len(), 4);
+    assert_eq!(buffer.label, None);
+
+    let builder: BufferBuilder<2> = Buffer::builder();
+    let err = builder.build().err().unwrap();
+    assert_eq!(err, BufferBuilderError::MissingData);
+}
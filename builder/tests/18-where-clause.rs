@@ -0,0 +1,64 @@
+// Type, lifetime and const parameters together with a where-clause, in both
+// the default and the typestate builder.
+
+use derive_builder::Builder;
+use std::fmt::Debug;
+
+pub trait Body: Debug {
+    fn len(&self) -> usize;
+}
+
+impl Body for Vec<u8> {
+    fn len(&self) -> usize {
+        Vec::len(self)
+    }
+}
+
+#[derive(Builder)]
+pub struct Request<'a, B: Body, const N: usize>
+where
+    B: Clone + Send,
+{
+    path: &'a str,
+    body: B,
+    headers: [&'a str; N],
+    #[builder(each = "query")]
+    queries: Vec<(&'a str, &'a str)>,
+    timeout: Option<u64>,
+}
+
+#[derive(Builder)]
+#[builder(typestate)]
+pub struct Response<'a, B: Body, const N: usize>
+where
+    B: Send,
+{
+    status: u16,
+    body: B,
+    headers: [&'a str; N],
+    reason: Option<&'a str>,
+}
+
+fn main() {
+    let request = Request::builder()
+        .path("/")
+        .body(vec![1, 2, 3])
+        .headers(["Accept: */*"])
+        .query(("page", "2"))
+        .build()
+        .unwrap();
+    assert_eq!(request.path, "/");
+    assert_eq!(request.body.len(), 3);
+    assert_eq!(request.queries, [("page", "2")]);
+    assert_eq!(request.timeout, None);
+
+    let response = Response::builder()
+        .headers(["Content-Length: 0", "Connection: close"])
+        .status(200)
+        .body(Vec::new())
+        .build();
+    assert_eq!(response.status, 200);
+    assert_eq!(response.body.len(), 0);
+    assert_eq!(response.headers.len(), 2);
+    assert_eq!(response.reason, None);
+}
@@ -0,0 +1,56 @@
+// #[builder(setter(into))] makes a setter generic over `impl Into<T>`, either
+// per field or for every field when given on the struct. For `each` fields
+// both the collection setter and the per-item setter convert their argument.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+pub struct Command {
+    #[builder(setter(into))]
+    executable: String,
+    #[builder(each = "arg", setter(into))]
+    args: Vec<String>,
+    env: Vec<String>,
+    #[builder(setter(into))]
+    current_dir: Option<String>,
+}
+
+#[derive(Builder)]
+#[builder(setter(into))]
+pub struct Server {
+    host: String,
+    port: u16,
+    #[builder(setter(into = false))]
+    name: String,
+}
+
+fn main() {
+    let command = Command::builder()
+        .executable("cargo")
+        .arg("build")
+        .arg(String::from("--release"))
+        .env(vec![])
+        .current_dir("..")
+        .build()
+        .unwrap();
+    assert_eq!(command.executable, "cargo");
+    assert_eq!(command.args, vec!["build", "--release"]);
+    assert_eq!(command.current_dir.as_deref(), Some(".."));
+
+    let command = Command::builder()
+        .executable("cargo")
+        .args(vec!["test".to_owned()])
+        .env(vec![])
+        .build()
+        .unwrap();
+    assert_eq!(command.args, vec!["test"]);
+
+    let server = Server::builder()
+        .host("localhost")
+        .port(80u8)
+        .name("api".to_owned())
+        .build()
+        .unwrap();
+    assert_eq!(server.host, "localhost");
+    assert_eq!(server.port, 80);
+}
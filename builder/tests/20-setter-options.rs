@@ -0,0 +1,50 @@
+// setter(strip_option = false) makes an Option<T> setter take the Option
+// itself, setter(name = "...") renames a setter, and setter(skip) leaves a
+// field without any setter so that it always takes its default.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+pub struct Command {
+    #[builder(setter(name = "exe", into))]
+    executable: String,
+    #[builder(setter(name = "arguments"), each = "arg")]
+    args: Vec<String>,
+    #[builder(setter(strip_option = false))]
+    current_dir: Option<String>,
+    #[builder(setter(skip), default = "std::process::id()")]
+    pid: u32,
+    #[builder(setter(skip))]
+    exit_code: Option<i32>,
+}
+
+#[derive(Builder)]
+#[builder(setter(strip_option = false))]
+pub struct Limits {
+    timeout_secs: Option<u64>,
+    #[builder(setter(strip_option))]
+    retries: Option<u32>,
+}
+
+fn main() {
+    let command = Command::builder()
+        .exe("cargo")
+        .arguments(vec!["build".to_owned()])
+        .arg("--release".to_owned())
+        .current_dir(None)
+        .build()
+        .unwrap();
+    assert_eq!(command.executable, "cargo");
+    assert_eq!(command.args, vec!["build", "--release"]);
+    assert_eq!(command.current_dir, None);
+    assert_eq!(command.pid, std::process::id());
+    assert_eq!(command.exit_code, None);
+
+    let limits = Limits::builder()
+        .timeout_secs(Some(30))
+        .retries(3)
+        .build()
+        .unwrap();
+    assert_eq!(limits.timeout_secs, Some(30));
+    assert_eq!(limits.retries, Some(3));
+}
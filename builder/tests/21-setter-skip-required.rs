@@ -0,0 +1,12 @@
+// A field without a setter can never be set, so it needs a default.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+pub struct Command {
+    executable: String,
+    #[builder(setter(skip))]
+    pid: u32,
+}
+
+fn main() {}
@@ -0,0 +1,60 @@
+// With #[builder(pattern = "owned")] setters take and return the builder by
+// value and build() consumes it, moving each field into the struct. Fields
+// therefore do not need to be Clone, and the builder can be threaded through
+// functions that return it.
+
+use derive_builder::Builder;
+use std::sync::mpsc::{self, Receiver};
+
+pub struct Handle {
+    fd: i32,
+}
+
+#[derive(Builder)]
+#[builder(pattern = "owned", build_fn(validate = "check_handle"))]
+pub struct Worker<T> {
+    handle: Handle,
+    events: Receiver<T>,
+    #[builder(each = "tag")]
+    tags: Vec<String>,
+    name: Option<String>,
+}
+
+fn check_handle<T>(builder: &WorkerBuilder<T>) -> Result<(), String> {
+    match &builder.handle {
+        Some(handle) if handle.fd < 0 => Err(format!("invalid fd {}", handle.fd)),
+        _ => Ok(()),
+    }
+}
+
+fn with_defaults<T>(builder: WorkerBuilder<T>) -> WorkerBuilder<T> {
+    builder.tag("default".to_owned()).name("worker".to_owned())
+}
+
+fn main() {
+    let (sender, receiver) = mpsc::channel();
+    let worker = with_defaults(Worker::builder())
+        .handle(Handle { fd: 3 })
+        .events(receiver)
+        .tag("io".to_owned())
+        .build()
+        .unwrap();
+
+    sender.send(42).unwrap();
+    assert_eq!(worker.events.recv().unwrap(), 42);
+    assert_eq!(worker.handle.fd, 3);
+    assert_eq!(worker.tags, vec!["default", "io"]);
+    assert_eq!(worker.name.as_deref(), Some("worker"));
+
+    let (_sender, receiver) = mpsc::channel::<u8>();
+    let err = Worker::builder()
+        .handle(Handle { fd: -1 })
+        .events(receiver)
+        .build()
+        .err()
+        .unwrap();
+    assert_eq!(err, WorkerBuilderError::Validation("invalid fd -1".to_owned()));
+
+    let err = Worker::<u8>::builder().build().err().unwrap();
+    assert_eq!(err, WorkerBuilderError::MissingHandle);
+}
@@ -0,0 +1,32 @@
+// With #[builder(pattern = "immutable")] setters take &self and return a
+// modified copy, so a partially configured builder can be reused as the base
+// for several variants.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+#[builder(pattern = "immutable")]
+pub struct Command {
+    executable: String,
+    #[builder(each = "arg")]
+    args: Vec<String>,
+    current_dir: Option<String>,
+}
+
+fn main() {
+    let base = Command::builder()
+        .executable("cargo".to_owned())
+        .arg("build".to_owned());
+
+    let release = base.arg("--release".to_owned()).build().unwrap();
+    let debug = base.current_dir("..".to_owned()).build().unwrap();
+
+    assert_eq!(release.args, vec!["build", "--release"]);
+    assert!(release.current_dir.is_none());
+    assert_eq!(debug.args, vec!["build"]);
+    assert_eq!(debug.current_dir.as_deref(), Some(".."));
+
+    // The base builder itself is unchanged.
+    let plain = base.build().unwrap();
+    assert_eq!(plain.args, vec!["build"]);
+}
@@ -0,0 +1,65 @@
+// #[builder(each = "...")] works with any collection that is `Default` and
+// `Extend`. Maps get a two-argument `key, value` setter, sets and other std
+// collections a one-argument setter, and any other type a setter generic over
+// whatever it can be extended with. Every `each` field also gets an
+// `extend_<field>` method adding all items of an iterator.
+
+use derive_builder::Builder;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
+
+#[derive(Clone, Default, Debug, PartialEq)]
+pub struct Lines(Vec<String>);
+
+impl<'a> Extend<&'a str> for Lines {
+    fn extend<I: IntoIterator<Item = &'a str>>(&mut self, iter: I) {
+        self.0.extend(iter.into_iter().map(str::to_owned));
+    }
+}
+
+#[derive(Builder)]
+pub struct Command {
+    executable: String,
+    #[builder(each = "arg")]
+    args: VecDeque<String>,
+    #[builder(each = "env")]
+    env: HashMap<String, String>,
+    #[builder(each = "label", setter(into))]
+    labels: BTreeMap<String, String>,
+    #[builder(each = "feature")]
+    features: HashSet<String>,
+    #[builder(each = "tag")]
+    tags: BTreeSet<u32>,
+    #[builder(each = "line")]
+    script: Lines,
+}
+
+fn main() {
+    let command = Command::builder()
+        .executable("cargo".to_owned())
+        .arg("build".to_owned())
+        .extend_args(vec!["--release".to_owned(), "--locked".to_owned()])
+        .env("RUST_LOG".to_owned(), "debug".to_owned())
+        .label("team", "build")
+        .extend_labels([("owner".to_owned(), "ci".to_owned())])
+        .feature("serde".to_owned())
+        .feature("serde".to_owned())
+        .tag(2)
+        .tag(1)
+        .line("set -e")
+        .extend_script(["cargo fmt", "cargo test"])
+        .build()
+        .unwrap();
+
+    assert_eq!(command.args, ["build", "--release", "--locked"]);
+    assert_eq!(command.env["RUST_LOG"], "debug");
+    assert_eq!(
+        command.labels.into_iter().collect::<Vec<_>>(),
+        [
+            ("owner".to_owned(), "ci".to_owned()),
+            ("team".to_owned(), "build".to_owned()),
+        ],
+    );
+    assert_eq!(command.features.len(), 1);
+    assert_eq!(command.tags.into_iter().collect::<Vec<_>>(), [1, 2]);
+    assert_eq!(command.script.0, ["set -e", "cargo fmt", "cargo test"]);
+}
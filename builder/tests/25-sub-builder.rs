@@ -0,0 +1,89 @@
+// #[builder(sub_builder)] on a field whose type also derives Builder replaces
+// its setter with a method returning the nested builder, which build() builds
+// along with the outer one. A missing field in the nested builder is reported
+// with a dotted path. On an Option<T> field the nested builder is only built,
+// and the field only Some, if the nested builder was accessed.
+
+use derive_builder::Builder;
+
+#[derive(Builder, Debug)]
+pub struct TlsConfig {
+    cert_path: String,
+    key_path: String,
+}
+
+mod limits {
+    use derive_builder::Builder;
+
+    #[derive(Builder, Debug)]
+    pub struct Limits {
+        #[builder(default = "64")]
+        pub max_connections: u32,
+        #[builder(default = "30")]
+        pub timeout_secs: u64,
+    }
+}
+
+#[derive(Builder, Debug)]
+pub struct Upstream {
+    #[builder(sub_builder)]
+    tls: TlsConfig,
+}
+
+#[derive(Builder, Debug)]
+pub struct ServerConfig {
+    host: String,
+    #[builder(sub_builder)]
+    tls: TlsConfig,
+    #[builder(sub_builder)]
+    limits: limits::Limits,
+    #[builder(sub_builder)]
+    upstream: Option<Upstream>,
+}
+
+fn main() {
+    let mut builder = ServerConfig::builder();
+    builder.host("localhost".to_owned());
+    builder.tls().cert_path("cert.pem".to_owned());
+    builder.tls().key_path("key.pem".to_owned());
+    builder.limits().max_connections(8);
+    let config = builder.build().unwrap();
+    assert_eq!(config.tls.cert_path, "cert.pem");
+    assert_eq!(config.tls.key_path, "key.pem");
+    assert_eq!(config.limits.max_connections, 8);
+    assert_eq!(config.limits.timeout_secs, 30);
+    assert!(config.upstream.is_none());
+
+    // The nested builder's error is wrapped in the outer one.
+    let mut builder = ServerConfig::builder();
+    builder.host("localhost".to_owned());
+    builder.tls().cert_path("cert.pem".to_owned());
+    let err = builder.build().unwrap_err();
+    assert_eq!(
+        err,
+        ServerConfigBuilderError::Tls(TlsConfigBuilderError::MissingKeyPath),
+    );
+    assert_eq!(err.field_path().as_deref(), Some("tls.key_path"));
+    assert_eq!(err.to_string(), "missing required field `tls.key_path`");
+
+    // An untouched nested builder is still built, so its required fields
+    // are reported as missing.
+    let err = ServerConfig::builder()
+        .host("localhost".to_owned())
+        .build()
+        .unwrap_err();
+    assert_eq!(err.to_string(), "missing required field `tls.cert_path`");
+
+    // Paths compose through several levels of nesting.
+    builder.tls().key_path("key.pem".to_owned());
+    builder.upstream().tls().cert_path("upstream.pem".to_owned());
+    let err = builder.build().unwrap_err();
+    assert_eq!(
+        err.field_path().as_deref(),
+        Some("upstream.tls.key_path"),
+    );
+
+    builder.upstream().tls().key_path("upstream.key".to_owned());
+    let config = builder.build().unwrap();
+    assert_eq!(config.upstream.unwrap().tls.cert_path, "upstream.pem");
+}
@@ -0,0 +1,82 @@
+// A built value converts back into a builder with every field set, either by
+// value through `From` or by cloning through `to_builder()`. Collections
+// filled through `each` setters keep their items, so further calls append.
+
+use derive_builder::Builder;
+
+#[derive(Builder, Debug, Clone, PartialEq)]
+pub struct Command {
+    executable: String,
+    #[builder(each = "arg")]
+    args: Vec<String>,
+    current_dir: Option<String>,
+}
+
+#[derive(Builder, Debug)]
+pub struct TlsConfig {
+    cert_path: String,
+}
+
+#[derive(Builder, Debug)]
+pub struct Server {
+    port: u16,
+    #[builder(sub_builder)]
+    tls: TlsConfig,
+}
+
+#[derive(Builder, Debug)]
+#[builder(typestate)]
+pub struct Job {
+    name: String,
+    retries: Option<u8>,
+}
+
+// Not Clone, so there is no `to_builder()` for `Handle`, but `From` works.
+#[derive(Debug)]
+pub struct Socket;
+
+#[derive(Builder, Debug)]
+#[builder(pattern = "owned")]
+pub struct Handle {
+    socket: Socket,
+    name: String,
+}
+
+fn main() {
+    let command = Command::builder()
+        .executable("cargo".to_owned())
+        .arg("build".to_owned())
+        .current_dir("..".to_owned())
+        .build()
+        .unwrap();
+
+    let verbose = command.to_builder().arg("--verbose".to_owned()).build().unwrap();
+    assert_eq!(verbose.args, ["build", "--verbose"]);
+    assert_eq!(verbose.current_dir.as_deref(), Some(".."));
+
+    let same = CommandBuilder::from(command.clone()).build().unwrap();
+    assert_eq!(same, command);
+
+    let mut builder = Server::builder();
+    builder.port(443).tls().cert_path("cert.pem".to_owned());
+    let server = builder.build().unwrap();
+    let mut builder = ServerBuilder::from(server);
+    builder.tls().cert_path("other.pem".to_owned());
+    let server = builder.build().unwrap();
+    assert_eq!(server.port, 443);
+    assert_eq!(server.tls.cert_path, "other.pem");
+
+    // Every required field of a typestate builder is already set.
+    let job = Job::builder().name("nightly".to_owned()).build();
+    let job = JobBuilder::from(job).retries(3).build();
+    assert_eq!(job.name, "nightly");
+    assert_eq!(job.retries, Some(3));
+
+    let handle = Handle::builder()
+        .socket(Socket)
+        .name("a".to_owned())
+        .build()
+        .unwrap();
+    let handle = HandleBuilder::from(handle).name("b".to_owned()).build().unwrap();
+    assert_eq!(handle.name, "b");
+}
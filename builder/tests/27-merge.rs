@@ -0,0 +1,65 @@
+// Builders filled from several sources can be layered. `merge()` overrides the
+// fields of one builder with those set in another, and `or()` fills in the
+// fields a builder is missing from a fallback. A collection set in both is
+// replaced by default, or extended with `#[builder(merge = "append")]`.
+
+use derive_builder::Builder;
+
+#[derive(Builder, Debug)]
+pub struct Limits {
+    #[builder(default = "64")]
+    max_connections: u32,
+    #[builder(default = "30")]
+    timeout_secs: u64,
+}
+
+#[derive(Builder, Debug)]
+pub struct Command {
+    executable: String,
+    #[builder(each = "arg", merge = "append")]
+    args: Vec<String>,
+    #[builder(each = "env", merge = "replace")]
+    env: Vec<String>,
+    current_dir: Option<String>,
+    #[builder(sub_builder)]
+    limits: Limits,
+}
+
+fn main() {
+    let mut file = Command::builder();
+    file.executable("cargo".to_owned())
+        .arg("build".to_owned())
+        .env("A=1".to_owned())
+        .current_dir("/srv".to_owned());
+    file.limits().max_connections(8).timeout_secs(10);
+
+    let mut env = Command::builder();
+    env.env("B=2".to_owned());
+    env.limits().timeout_secs(5);
+
+    let mut cli = Command::builder();
+    cli.executable("cargo-nightly".to_owned())
+        .arg("--release".to_owned());
+
+    let command = file.merge(env).merge(cli).build().unwrap();
+    assert_eq!(command.executable, "cargo-nightly");
+    assert_eq!(command.args, ["build", "--release"]);
+    assert_eq!(command.env, ["B=2"]);
+    assert_eq!(command.current_dir.as_deref(), Some("/srv"));
+    assert_eq!(command.limits.max_connections, 8);
+    assert_eq!(command.limits.timeout_secs, 5);
+
+    // `or()` keeps what is set and takes the rest from the fallback.
+    let mut defaults = Command::builder();
+    defaults
+        .executable("sh".to_owned())
+        .current_dir("/".to_owned())
+        .arg("-e".to_owned());
+    let mut cli = Command::builder();
+    cli.executable("bash".to_owned()).arg("-x".to_owned());
+
+    let command = cli.or(defaults).build().unwrap();
+    assert_eq!(command.executable, "bash");
+    assert_eq!(command.args, ["-e", "-x"]);
+    assert_eq!(command.current_dir.as_deref(), Some("/"));
+}
@@ -0,0 +1,13 @@
+// Appending only makes sense for a collection that is extended one item at a
+// time, so `merge = "append"` requires `each`.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+pub struct Command {
+    executable: String,
+    #[builder(merge = "append")]
+    args: Vec<String>,
+}
+
+fn main() {}
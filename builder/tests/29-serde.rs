@@ -0,0 +1,59 @@
+// With the `serde` feature, #[builder(serde)] makes the builder derive
+// Deserialize so that a partial configuration can be loaded into it and
+// completed in code. Every field may be missing, `each` fields take arrays and
+// nested builders take objects. #[builder(serde(...))] also passes its
+// contents through to the builder as #[serde(...)].
+
+use derive_builder::Builder;
+
+#[derive(Builder, Debug)]
+#[builder(serde, serde(deny_unknown_fields))]
+pub struct Limits {
+    #[builder(default = "64")]
+    max_connections: u32,
+    #[builder(default = "30")]
+    timeout_secs: u64,
+}
+
+#[derive(Builder, Debug)]
+#[builder(serde(rename_all = "kebab-case"))]
+pub struct Command {
+    executable: String,
+    #[builder(each = "arg")]
+    args: Vec<String>,
+    #[builder(serde(rename = "cwd"))]
+    current_dir: Option<String>,
+    #[builder(sub_builder)]
+    limits: Limits,
+    dry_run: bool,
+}
+
+fn main() {
+    let mut builder: CommandBuilder = serde_json::from_str(
+        r#"{
+            "args": ["build", "--release"],
+            "cwd": "/srv",
+            "limits": { "timeout_secs": 5 }
+        }"#,
+    )
+    .unwrap();
+    builder.executable("cargo".to_owned()).arg("--locked".to_owned());
+
+    // `rename_all` applies to this builder only, not the nested one.
+    // A field missing from the configuration is still reported by build().
+    let err = builder.build().unwrap_err();
+    assert_eq!(err, CommandBuilderError::MissingDryRun);
+
+    let command = builder.dry_run(true).build().unwrap();
+    assert_eq!(command.executable, "cargo");
+    assert_eq!(command.args, ["build", "--release", "--locked"]);
+    assert_eq!(command.current_dir.as_deref(), Some("/srv"));
+    assert_eq!(command.limits.max_connections, 64);
+    assert_eq!(command.limits.timeout_secs, 5);
+    assert!(command.dry_run);
+
+    let builder: CommandBuilder = serde_json::from_str(r#"{ "dry-run": false }"#).unwrap();
+    assert_eq!(builder.build().unwrap_err(), CommandBuilderError::MissingExecutable);
+
+    assert!(serde_json::from_str::<LimitsBuilder>(r#"{ "retries": 3 }"#).is_err());
+}
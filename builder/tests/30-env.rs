@@ -0,0 +1,81 @@
+// `from_env()` returns a builder with fields read from environment variables
+// and parsed with FromStr: those named by #[builder(env = "...")], and with
+// #[builder(env_prefix = "...")] every field, from the prefix followed by the
+// field name in upper case. Collections are split on `env_separator`, a comma
+// unless given otherwise. Variables that are not set leave their field unset,
+// and values that fail to parse are reported with the variable's name.
+// #[builder(env(skip))] leaves a field out of the prefix, as a field whose
+// type does not implement FromStr must be.
+
+use derive_builder::Builder;
+use std::collections::BTreeSet;
+use std::env;
+use std::time::Duration;
+
+#[derive(Builder, Debug)]
+#[builder(env_prefix = "APP_")]
+pub struct Command {
+    #[builder(env = "APP_EXE")]
+    executable: String,
+    args: Vec<String>,
+    #[builder(env_separator = ":")]
+    paths: Vec<String>,
+    #[builder(each = "tag")]
+    tags: BTreeSet<u32>,
+    jobs: Option<u8>,
+    #[builder(default)]
+    verbose: bool,
+    #[builder(env(skip), default = "Duration::from_secs(30)")]
+    timeout: Duration,
+}
+
+#[derive(Builder, Debug)]
+pub struct Server {
+    #[builder(env = "SERVER_PORT")]
+    port: u16,
+    host: String,
+}
+
+fn main() {
+    env::set_var("APP_EXE", "cargo");
+    env::set_var("APP_ARGS", "build,--release");
+    env::set_var("APP_PATHS", "/bin:/usr/bin");
+    env::set_var("APP_TAGS", "3,1,2");
+    env::set_var("APP_JOBS", "4");
+
+    let command = CommandBuilder::from_env().unwrap().build().unwrap();
+    assert_eq!(command.executable, "cargo");
+    assert_eq!(command.args, ["build", "--release"]);
+    assert_eq!(command.paths, ["/bin", "/usr/bin"]);
+    assert_eq!(command.tags.into_iter().collect::<Vec<_>>(), [1, 2, 3]);
+    assert_eq!(command.jobs, Some(4));
+    assert!(!command.verbose);
+    assert_eq!(command.timeout, Duration::from_secs(30));
+
+    // The builder can still be completed or overridden in code.
+    env::remove_var("APP_EXE");
+    let err = CommandBuilder::from_env().unwrap().build().unwrap_err();
+    assert_eq!(err, CommandBuilderError::MissingExecutable);
+
+    env::set_var("APP_JOBS", "many");
+    let err = CommandBuilder::from_env().err().unwrap();
+    assert_eq!(
+        err,
+        CommandBuilderError::Env {
+            var: "APP_JOBS".to_owned(),
+            message: "invalid digit found in string".to_owned(),
+        },
+    );
+    assert_eq!(
+        err.to_string(),
+        "invalid value in environment variable `APP_JOBS`: invalid digit found in string",
+    );
+
+    env::set_var("SERVER_PORT", "8080");
+    let server = ServerBuilder::from_env()
+        .unwrap()
+        .host("localhost".to_owned())
+        .build()
+        .unwrap();
+    assert_eq!(server.port, 8080);
+}
@@ -0,0 +1,18 @@
+// A map has no obvious representation in a single environment variable, so
+// naming a variable for one is an error. With only an `env_prefix`, such
+// fields are left out of `from_env()` instead.
+
+use derive_builder::Builder;
+use std::collections::HashMap;
+
+#[derive(Builder)]
+#[builder(env_prefix = "APP_")]
+pub struct Command {
+    executable: String,
+    #[builder(each = "env")]
+    env: HashMap<String, String>,
+    #[builder(env = "APP_LABELS")]
+    labels: HashMap<String, String>,
+}
+
+fn main() {}
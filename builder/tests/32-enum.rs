@@ -0,0 +1,115 @@
+// On an enum, each variant with named fields gets its own builder, returned
+// by `<variant>_builder()` and named after the enum and the variant. Its
+// build() returns the enum, and its fields behave as on a struct's builder.
+// Variants marked #[builder(skip)] get no builder. A tuple variant opts in to
+// a builder by naming its fields with #[builder(field_names(...))], and a unit
+// variant with #[builder(unit)], for a builder without setters. A run of
+// capitals in a variant's name is one word of its builder function's name.
+
+use derive_builder::Builder;
+
+#[derive(Builder, Debug, PartialEq)]
+pub enum Shape {
+    Circle {
+        r: f64,
+    },
+    Rect {
+        w: f64,
+        h: f64,
+        label: Option<String>,
+    },
+    Polygon {
+        #[builder(each = "point")]
+        points: Vec<(f64, f64)>,
+    },
+    #[builder(field_names("side", "label"))]
+    Square(f64, Option<String>),
+    #[builder(unit)]
+    Point,
+    #[builder(skip)]
+    Empty,
+}
+
+#[derive(Builder, Debug, PartialEq)]
+#[builder(pattern = "owned", setter(into))]
+pub enum Message<T> {
+    Text { body: String },
+    Payload { value: T, retries: Option<u8> },
+}
+
+#[derive(Builder, Debug, PartialEq)]
+pub enum Request {
+    HTTPRequest { url: String },
+    RetryIO { attempts: u8 },
+}
+
+fn main() {
+    let circle = Shape::circle_builder().r(1.5).build().unwrap();
+    assert_eq!(circle, Shape::Circle { r: 1.5 });
+
+    let rect = Shape::rect_builder().w(2.0).h(3.0).build().unwrap();
+    assert_eq!(
+        rect,
+        Shape::Rect {
+            w: 2.0,
+            h: 3.0,
+            label: None,
+        },
+    );
+
+    let err = Shape::rect_builder().w(2.0).build().unwrap_err();
+    assert_eq!(err, ShapeRectBuilderError::MissingH);
+
+    let polygon = Shape::polygon_builder()
+        .point((0.0, 0.0))
+        .point((1.0, 0.0))
+        .point((0.0, 1.0))
+        .build()
+        .unwrap();
+    let Shape::Polygon { points } = polygon else {
+        panic!("expected a polygon");
+    };
+    assert_eq!(points.len(), 3);
+    assert_ne!(Shape::Empty, Shape::Circle { r: 0.0 });
+
+    let square = Shape::square_builder().side(2.0).build().unwrap();
+    assert_eq!(square, Shape::Square(2.0, None));
+    let err = Shape::square_builder().label("a".to_owned()).build();
+    assert_eq!(err.unwrap_err(), ShapeSquareBuilderError::MissingSide);
+    assert_eq!(Shape::point_builder().build().unwrap(), Shape::Point);
+
+    // A variant that does not use the enum's type parameter still has a
+    // builder generic over it.
+    let text: Message<u32> = Message::text_builder().body("hi").build().unwrap();
+    assert_eq!(
+        text,
+        Message::Text {
+            body: "hi".to_owned(),
+        },
+    );
+    let payload: Message<u64> = Message::payload_builder()
+        .value(7u32)
+        .retries(3)
+        .build()
+        .unwrap();
+    assert_eq!(
+        payload,
+        Message::Payload {
+            value: 7,
+            retries: Some(3),
+        },
+    );
+
+    let request = Request::http_request_builder()
+        .url("https://example.com".to_owned())
+        .build()
+        .unwrap();
+    assert_eq!(
+        request,
+        Request::HTTPRequest {
+            url: "https://example.com".to_owned(),
+        },
+    );
+    let request = Request::retry_io_builder().attempts(2).build().unwrap();
+    assert_eq!(request, Request::RetryIO { attempts: 2 });
+}
@@ -0,0 +1,21 @@
+// A variant's builder needs field names for its setters, so tuple variants
+// are an error unless they name their fields with
+// #[builder(field_names(...))], and unit variants unless they ask for a
+// builder without setters with #[builder(unit)]. Either can opt out of having
+// a builder with #[builder(skip)].
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+pub enum Shape {
+    Circle { r: f64 },
+    Square(f64),
+}
+
+#[derive(Builder)]
+pub enum Token {
+    Word { text: String },
+    End,
+}
+
+fn main() {}
@@ -0,0 +1,38 @@
+// Tuple structs get a builder too. Their fields are named `_0`, `_1`, ...
+// in the builder and its setters, or as given by
+// #[builder(field_names(...))].
+
+use derive_builder::Builder;
+
+#[derive(Builder, Debug, Clone, PartialEq)]
+#[builder(field_names("r", "g", "b"))]
+pub struct Rgb(u8, u8, u8);
+
+#[derive(Builder, Debug, PartialEq)]
+pub struct Tagged(String, #[builder(default)] Vec<String>, Option<u32>);
+
+#[derive(Builder, Debug, PartialEq, Default)]
+#[builder(default)]
+pub struct Point(i32, i32);
+
+fn main() {
+    let teal = Rgb::builder().r(0).g(128).b(128).build().unwrap();
+    assert_eq!(teal, Rgb(0, 128, 128));
+
+    let err = Rgb::builder().r(0).b(128).build().unwrap_err();
+    assert_eq!(err, RgbBuilderError::MissingG);
+    assert_eq!(err.to_string(), "missing required field `g`");
+
+    let lighter = teal.to_builder().r(64).build().unwrap();
+    assert_eq!(lighter, Rgb(64, 128, 128));
+
+    let tagged = Tagged::builder()._0("name".to_owned())._2(7).build().unwrap();
+    assert_eq!(tagged, Tagged("name".to_owned(), Vec::new(), Some(7)));
+    assert_eq!(
+        Tagged::builder().build().unwrap_err(),
+        TaggedBuilderError::Missing0,
+    );
+
+    let point = Point::builder()._1(5).build().unwrap();
+    assert_eq!(point, Point(0, 5));
+}
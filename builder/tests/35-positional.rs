@@ -0,0 +1,38 @@
+// #[builder(positional)] replaces `builder()` with a `new()` constructor
+// taking the required fields in order, so the builder only has setters for
+// the fields that are optional or have a default.
+
+use derive_builder::Builder;
+
+#[derive(Builder, Debug)]
+#[builder(positional)]
+pub struct Command {
+    #[builder(setter(into))]
+    executable: String,
+    #[builder(each = "arg")]
+    args: Vec<String>,
+    current_dir: Option<String>,
+    #[builder(default = "1")]
+    jobs: u8,
+    timeout_secs: u64,
+}
+
+#[derive(Builder, Debug, PartialEq)]
+#[builder(positional, pattern = "owned", field_names("x", "y"))]
+pub struct Point(i32, i32);
+
+fn main() {
+    let command = Command::new("cargo", 30)
+        .arg("build".to_owned())
+        .current_dir("..".to_owned())
+        .build()
+        .unwrap();
+    assert_eq!(command.executable, "cargo");
+    assert_eq!(command.timeout_secs, 30);
+    assert_eq!(command.args, ["build"]);
+    assert_eq!(command.current_dir.as_deref(), Some(".."));
+    assert_eq!(command.jobs, 1);
+
+    let point = Point::new(1, 2).build().unwrap();
+    assert_eq!(point, Point(1, 2));
+}
@@ -0,0 +1,8 @@
+// A unit struct has no fields to set, so there is nothing to build.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+pub struct Marker;
+
+fn main() {}
@@ -0,0 +1,12 @@
+// A union has exactly one field set at a time, which a builder cannot
+// express.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+pub union Bits {
+    int: u32,
+    float: f32,
+}
+
+fn main() {}
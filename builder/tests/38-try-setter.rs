@@ -0,0 +1,92 @@
+// #[builder(try_setter)] adds a `try_` setter next to the regular one, which
+// accepts anything `TryInto` the field's type and returns the conversion
+// error. With `try_setter(deferred)` the setter returns the builder as usual
+// and the conversion error is reported by `build()` instead, so the calls can
+// still be chained. Setting the field afterwards, through either setter,
+// drops its error.
+
+use derive_builder::Builder;
+use std::num::TryFromIntError;
+
+#[derive(Builder, Debug)]
+pub struct Server {
+    #[builder(try_setter)]
+    port: u16,
+    #[builder(try_setter(deferred))]
+    threads: u8,
+    #[builder(try_setter(deferred))]
+    backlog: Option<u32>,
+}
+
+#[derive(Builder, Debug)]
+#[builder(pattern = "immutable")]
+pub struct Client {
+    #[builder(try_setter(deferred))]
+    retries: u8,
+}
+
+#[derive(Builder, Debug)]
+#[builder(typestate)]
+pub struct Listener {
+    #[builder(try_setter)]
+    port: u16,
+    #[builder(try_setter, default)]
+    backlog: u32,
+}
+
+fn main() -> Result<(), TryFromIntError> {
+    let server = Server::builder()
+        .try_port(8080u32)?
+        .try_threads(4i64)
+        .try_backlog(128u64)
+        .build()
+        .unwrap();
+    assert_eq!(server.port, 8080);
+    assert_eq!(server.threads, 4);
+    assert_eq!(server.backlog, Some(128));
+
+    let mut builder = Server::builder();
+    assert!(builder.try_port(70000u32).is_err());
+    builder.port(80).try_threads(300).try_threads(-1).try_backlog(-1);
+    let err = builder.build().unwrap_err();
+    assert_eq!(
+        err,
+        ServerBuilderError::Conversion {
+            field: "threads".to_owned(),
+            message: "out of range integral type conversion attempted".to_owned(),
+        },
+    );
+    assert_eq!(
+        err.to_string(),
+        "invalid value for field `threads`: out of range integral type conversion attempted",
+    );
+
+    let mut builder = Server::builder();
+    builder.port(80).try_threads(300).try_threads(4).try_backlog(-1);
+    let err = builder.build().unwrap_err();
+    assert!(matches!(err, ServerBuilderError::Conversion { field, .. } if field == "backlog"));
+    builder.backlog(16);
+    let server = builder.build().unwrap();
+    assert_eq!((server.threads, server.backlog), (4, Some(16)));
+
+    // A merged builder brings along its errors for the fields it sets.
+    let mut builder = Server::builder();
+    builder.port(80).try_threads(300);
+    let mut other = Server::builder();
+    other.try_threads(2);
+    builder.merge(other);
+    assert_eq!(builder.build().unwrap().threads, 2);
+
+    let client = Client::builder();
+    assert!(client.try_retries(3).build().is_ok());
+    assert!(client.try_retries(1000).build().is_err());
+    assert!(client.try_retries(1000).try_retries(3).build().is_ok());
+    assert!(client.try_retries(1000).retries(3).build().is_ok());
+    assert!(client.build().is_err());
+
+    let listener = Listener::builder().try_port(443u64)?.build();
+    assert_eq!(listener.port, 443);
+    assert_eq!(listener.backlog, 0);
+
+    Ok(())
+}
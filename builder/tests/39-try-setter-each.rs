@@ -0,0 +1,12 @@
+// A `try_` setter converts a value for the field as a whole, so it is not
+// available on collections set one item at a time.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+pub struct Server {
+    #[builder(each = "port", try_setter)]
+    ports: Vec<u16>,
+}
+
+fn main() {}
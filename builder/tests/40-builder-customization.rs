@@ -0,0 +1,46 @@
+// The builder struct can be renamed with `name`, given a visibility other
+// than the struct's with `vis`, and made to derive traits with `derive`.
+// `field(vis = "...")` exposes individual fields of the builder, and doc
+// comments on the struct's fields are repeated on their setters.
+
+mod config {
+    use derive_builder::Builder;
+
+    #[derive(Builder, Debug)]
+    #[builder(name = "CommandOptions", vis = "pub(crate)", derive(Debug, Clone, PartialEq))]
+    pub struct Command {
+        /// The program to run.
+        executable: String,
+        /// Arguments passed to the program, in order.
+        #[builder(each = "arg", field(vis = "pub(crate)"))]
+        args: Vec<String>,
+        #[builder(default)]
+        current_dir: Option<String>,
+    }
+
+    impl Command {
+        pub fn executable(&self) -> &str {
+            &self.executable
+        }
+    }
+}
+
+use config::{Command, CommandOptions, CommandOptionsError};
+
+fn main() {
+    let mut base: CommandOptions = Command::builder();
+    base.executable("cargo".to_owned()).arg("build".to_owned());
+
+    let mut fork = base.clone();
+    assert_eq!(fork, base);
+    fork.arg("--release".to_owned());
+    assert_ne!(fork, base);
+    assert_eq!(fork.args.as_deref().map(<[String]>::len), Some(2));
+    assert!(format!("{:?}", fork).starts_with("CommandOptions {"));
+
+    let command = base.build().unwrap();
+    assert_eq!(command.executable(), "cargo");
+
+    let err = Command::builder().build().unwrap_err();
+    assert_eq!(err, CommandOptionsError::MissingExecutable);
+}
@@ -0,0 +1,72 @@
+// The builder reports which of its fields are set, which required fields are
+// still missing, and describes every field in a `FIELDS` table, classified
+// the same way `build()` decides whether an unset field is an error. A
+// typestate builder reports the same, whatever state it is in.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+pub struct Command {
+    executable: String,
+    #[builder(each = "arg")]
+    args: Vec<String>,
+    env: Vec<(String, String)>,
+    current_dir: Option<String>,
+    #[builder(default = "1")]
+    jobs: u8,
+    timeout_secs: u64,
+}
+
+#[derive(Builder)]
+#[builder(typestate, build_fn(name = "finish"))]
+pub struct Request {
+    url: String,
+    method: String,
+    body: Option<Vec<u8>>,
+}
+
+fn main() {
+    let mut builder = Command::builder();
+    assert_eq!(builder.missing_fields(), ["executable", "env", "timeout_secs"]);
+    assert!(!builder.is_complete());
+
+    builder.executable("cargo".to_owned()).arg("build".to_owned());
+    assert!(builder.is_set_executable());
+    assert!(builder.is_set_args());
+    assert!(!builder.is_set_current_dir());
+    assert_eq!(builder.missing_fields(), ["env", "timeout_secs"]);
+
+    builder.env(vec![]).timeout_secs(30);
+    assert!(builder.missing_fields().is_empty());
+    assert!(builder.is_complete());
+    assert!(builder.build().is_ok());
+
+    let fields: Vec<_> = CommandBuilder::FIELDS
+        .iter()
+        .map(|field| (field.name, field.ty, field.kind))
+        .collect();
+    assert_eq!(
+        fields,
+        [
+            ("executable", "String", CommandBuilderFieldKind::Required),
+            ("args", "Vec<String>", CommandBuilderFieldKind::Each),
+            ("env", "Vec<(String, String)>", CommandBuilderFieldKind::Required),
+            ("current_dir", "Option<String>", CommandBuilderFieldKind::Optional),
+            ("jobs", "u8", CommandBuilderFieldKind::Optional),
+            ("timeout_secs", "u64", CommandBuilderFieldKind::Required),
+        ],
+    );
+
+    let builder = Request::builder();
+    assert_eq!(builder.missing_fields(), ["url", "method"]);
+    assert!(!builder.is_complete());
+    let builder = builder.url("/".to_owned()).body(vec![]);
+    assert!(builder.is_set_url());
+    assert!(!builder.is_set_method());
+    assert!(builder.is_set_body());
+    assert_eq!(builder.missing_fields(), ["method"]);
+    let builder = builder.method("GET".to_owned());
+    assert!(builder.is_complete());
+    assert_eq!(builder.finish().method, "GET");
+    assert_eq!(RequestBuilder::FIELDS.len(), 3);
+}
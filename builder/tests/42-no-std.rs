@@ -0,0 +1,70 @@
+// With #[builder(no_std)] the generated code only refers to `core` and
+// `alloc`, for crates that are `#![no_std]` and declare `extern crate alloc`.
+//
+// This test links `std` under another name only to get a runtime for `main`;
+// since it is not called `std`, any `::std` path left in the generated code
+// would fail to resolve.
+
+#![no_std]
+
+extern crate alloc;
+extern crate std as runtime;
+
+use alloc::borrow::ToOwned;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use derive_builder::Builder;
+
+#[derive(Builder, Debug)]
+#[builder(no_std)]
+pub struct Command {
+    executable: String,
+    #[builder(each = "arg")]
+    args: Vec<String>,
+    #[builder(try_setter(deferred))]
+    jobs: u8,
+    #[builder(sub_builder)]
+    limits: Limits,
+}
+
+#[derive(Builder, Debug)]
+#[builder(no_std, build_fn(validate = "Self::validate"))]
+pub struct Limits {
+    #[builder(default = "60")]
+    timeout_secs: u64,
+}
+
+impl LimitsBuilder {
+    fn validate(&self) -> Result<(), String> {
+        match self.timeout_secs {
+            Some(0) => Err("timeout must not be zero".to_owned()),
+            _ => Ok(()),
+        }
+    }
+}
+
+fn assert_error<E: core::error::Error>(_: &E) {}
+
+fn main() {
+    let command = Command::builder()
+        .executable("cargo".to_owned())
+        .arg("build".to_owned())
+        .try_jobs(4u32)
+        .build()
+        .unwrap();
+    assert_eq!(command.executable, "cargo");
+    assert_eq!(command.args, ["build"]);
+    assert_eq!(command.jobs, 4);
+    assert_eq!(command.limits.timeout_secs, 60);
+
+    let err = Command::builder().build().unwrap_err();
+    assert_error(&err);
+    assert_eq!(err.to_string(), "missing required field `executable`");
+    assert_eq!(Command::builder().missing_fields(), ["executable", "jobs"]);
+
+    let mut builder = Command::builder();
+    builder.executable("cargo".to_owned()).jobs(1);
+    builder.limits().timeout_secs(0);
+    let err = builder.build().unwrap_err();
+    assert_eq!(err.to_string(), "`limits`: timeout must not be zero");
+}
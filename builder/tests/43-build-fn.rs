@@ -0,0 +1,117 @@
+// build_fn(...) renames the generated build function, makes it return an
+// error type of the user's choosing, makes it private, or runs a hook on the
+// built value before returning it. The error type is converted to with
+// `From`: from the generated `<Builder>UninitializedFieldError` for a missing
+// field, and from the builder's own error for anything else, such as a
+// failed validation.
+
+mod command {
+    use derive_builder::Builder;
+
+    #[derive(Debug, PartialEq)]
+    pub enum ConfigError {
+        Missing(&'static str),
+        Invalid(String),
+    }
+
+    impl From<CommandBuilderUninitializedFieldError> for ConfigError {
+        fn from(err: CommandBuilderUninitializedFieldError) -> Self {
+            ConfigError::Missing(err.field_name())
+        }
+    }
+
+    impl From<ServiceBuilderUninitializedFieldError> for ConfigError {
+        fn from(err: ServiceBuilderUninitializedFieldError) -> Self {
+            ConfigError::Missing(err.field_name())
+        }
+    }
+
+    impl From<ServiceBuilderError> for ConfigError {
+        fn from(err: ServiceBuilderError) -> Self {
+            ConfigError::Invalid(err.to_string())
+        }
+    }
+
+    #[derive(Builder, Debug)]
+    #[builder(build_fn(
+        name = "finish",
+        error = "ConfigError",
+        private,
+        post_build = "Command::normalize",
+    ))]
+    pub struct Command {
+        pub executable: String,
+        #[builder(each = "arg")]
+        pub args: Vec<String>,
+    }
+
+    impl Command {
+        fn normalize(&mut self) {
+            self.executable = self.executable.trim().to_owned();
+        }
+    }
+
+    impl CommandBuilder {
+        pub fn build(&self) -> Result<Command, ConfigError> {
+            self.finish()
+        }
+    }
+
+    #[derive(Builder, Debug)]
+    #[builder(build_fn(error = "ConfigError", validate = "Self::check"))]
+    pub struct Service {
+        pub name: String,
+        #[builder(default = "8080")]
+        pub port: u16,
+    }
+
+    impl ServiceBuilder {
+        fn check(&self) -> Result<(), String> {
+            match self.port {
+                Some(0) => Err("port must not be 0".to_owned()),
+                _ => Ok(()),
+            }
+        }
+    }
+
+    #[derive(Builder, Debug)]
+    #[builder(typestate, build_fn(name = "finish", post_build = "Listener::normalize"))]
+    pub struct Listener {
+        pub host: String,
+    }
+
+    impl Listener {
+        fn normalize(&mut self) {
+            self.host.make_ascii_lowercase();
+        }
+    }
+}
+
+use command::{Command, ConfigError, Listener, Service};
+
+fn main() {
+    let command = Command::builder()
+        .executable(" cargo ".to_owned())
+        .arg("build".to_owned())
+        .build()
+        .unwrap();
+    assert_eq!(command.executable, "cargo");
+    assert_eq!(command.args, ["build"]);
+
+    let err = Command::builder().build().unwrap_err();
+    assert_eq!(err, ConfigError::Missing("executable"));
+
+    let service = Service::builder().name("api".to_owned()).build().unwrap();
+    assert_eq!(service.port, 8080);
+    let err = Service::builder().build().unwrap_err();
+    assert_eq!(err, ConfigError::Missing("name"));
+    let err = Service::builder()
+        .name("api".to_owned())
+        .port(0)
+        .build()
+        .unwrap_err();
+    assert_eq!(err, ConfigError::Invalid("port must not be 0".to_owned()));
+
+    let listener = Listener::builder().host("LocalHost".to_owned()).finish();
+    assert_eq!(listener.host, "localhost");
+}
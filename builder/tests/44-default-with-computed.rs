@@ -0,0 +1,80 @@
+// #[builder(default_with = "...")] gives an unset field the value of a
+// closure called with the builder, once the explicitly set fields are known.
+// The closure sees the builder as it was set, without the defaults of other
+// fields, which is why `z` below gets -1 rather than `y`'s default.
+// #[builder(computed = "...")] fields have no setter at all; `build()`
+// evaluates the expression, which like `default = "..."` may refer to the
+// other fields by name, including through format string captures such as
+// `{name}`, in whatever order their dependencies require. A name in a path,
+// such as `defaults::timeout()`, is not a field.
+
+use derive_builder::Builder;
+use std::path::PathBuf;
+
+mod defaults {
+    pub fn timeout() -> u64 {
+        30
+    }
+
+    pub fn retries(timeout: u64) -> u32 {
+        (timeout / 10) as u32
+    }
+}
+
+#[derive(Builder, Debug)]
+pub struct Command {
+    executable: String,
+    #[builder(each = "arg")]
+    args: Vec<String>,
+    #[builder(default = "PathBuf::from(\".\")")]
+    current_dir: PathBuf,
+    #[builder(default = "current_dir.join(format!(\"{name}.log\"))")]
+    log_file: PathBuf,
+    #[builder(computed = "executable.rsplit('/').next().unwrap_or_default().to_owned()")]
+    name: String,
+    #[builder(default_with = "|b: &CommandBuilder| b.args.as_ref().map_or(0, |args| args.len()) as u8 + 1")]
+    jobs: u8,
+    #[builder(default = "defaults::retries(timeout)")]
+    retries: u32,
+    #[builder(default = "defaults::timeout()")]
+    timeout: u64,
+}
+
+#[derive(Builder, Debug)]
+#[builder(pattern = "owned")]
+pub struct Point {
+    x: i32,
+    #[builder(default_with = "|b: &PointBuilder| b.x.unwrap_or_default()")]
+    y: i32,
+    #[builder(default_with = "|b: &PointBuilder| b.y.unwrap_or(-1)")]
+    z: i32,
+}
+
+fn main() {
+    let command = Command::builder()
+        .executable("/usr/bin/cargo".to_owned())
+        .arg("build".to_owned())
+        .arg("--release".to_owned())
+        .build()
+        .unwrap();
+    assert_eq!(command.name, "cargo");
+    assert_eq!(command.log_file, PathBuf::from("./cargo.log"));
+    assert_eq!(command.jobs, 3);
+    assert_eq!((command.timeout, command.retries), (30, 3));
+
+    let command = Command::builder()
+        .executable("make".to_owned())
+        .current_dir("/tmp".into())
+        .jobs(8)
+        .timeout(50)
+        .build()
+        .unwrap();
+    assert_eq!(command.log_file, PathBuf::from("/tmp/make.log"));
+    assert_eq!(command.jobs, 8);
+    assert_eq!((command.timeout, command.retries), (50, 5));
+
+    let point = Point::builder().x(3).build().unwrap();
+    assert_eq!((point.x, point.y, point.z), (3, 3, -1));
+    let point = Point::builder().x(1).y(2).build().unwrap();
+    assert_eq!((point.x, point.y, point.z), (1, 2, 2));
+}
@@ -0,0 +1,14 @@
+// Defaults and computed fields referring to each other in a cycle could never
+// be evaluated, which is reported on the first expression in the cycle.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+pub struct Window {
+    #[builder(default = "height * 4 / 3")]
+    width: u32,
+    #[builder(computed = "width * 3 / 4")]
+    height: u32,
+}
+
+fn main() {}
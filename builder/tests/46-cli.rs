@@ -0,0 +1,93 @@
+// #[builder(cli)] generates `parse_args()`, which fills the builder from
+// command-line flags named after the setters: `--current-dir /tmp` or
+// `--current-dir=/tmp` calls `current_dir`, and each `--arg x` adds an item
+// through the `each = "arg"` setter. Values are parsed with FromStr, and
+// `bool` fields are switches. `--help`, unknown arguments and values that
+// fail to parse come back as errors, `--help` displaying the generated help
+// text built from the fields' doc comments. #[builder(cli(skip))] leaves a
+// field without a flag, as a field whose type does not implement FromStr must
+// be; it keeps its setter.
+
+use derive_builder::Builder;
+use std::time::Duration;
+
+#[derive(Builder, Debug)]
+#[builder(cli, derive(Debug))]
+pub struct Command {
+    /// The program to run.
+    executable: String,
+    /// An argument passed to the program.
+    ///
+    /// Repeat the flag for more arguments.
+    #[builder(each = "arg")]
+    args: Vec<String>,
+    /// Where the program runs.
+    current_dir: Option<String>,
+    /// How many jobs run in parallel.
+    #[builder(default = "1")]
+    jobs: u8,
+    #[builder(default)]
+    verbose: bool,
+    /// How long the program may run.
+    #[builder(cli(skip), default = "Duration::from_secs(60)")]
+    timeout: Duration,
+}
+
+fn args(args: &[&str]) -> Vec<String> {
+    args.iter().map(|arg| arg.to_string()).collect()
+}
+
+fn main() {
+    let command = CommandBuilder::parse_args(args(&[
+        "--executable",
+        "cargo",
+        "--arg",
+        "build",
+        "--arg=--release",
+        "--current-dir=/tmp",
+        "--verbose",
+    ]))
+    .unwrap()
+    .build()
+    .unwrap();
+    assert_eq!(command.executable, "cargo");
+    assert_eq!(command.args, ["build", "--release"]);
+    assert_eq!(command.current_dir.as_deref(), Some("/tmp"));
+    assert_eq!(command.jobs, 1);
+    assert!(command.verbose);
+    assert_eq!(command.timeout, Duration::from_secs(60));
+
+    let err = CommandBuilder::parse_args(args(&["--jobs", "many"])).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "invalid value for `--jobs`: invalid digit found in string",
+    );
+    let err = CommandBuilder::parse_args(args(&["--exe", "cargo"])).unwrap_err();
+    assert_eq!(err, CommandBuilderError::UnknownArgument("--exe".to_owned()));
+    let err = CommandBuilder::parse_args(args(&["--timeout", "5"])).unwrap_err();
+    assert_eq!(err, CommandBuilderError::UnknownArgument("--timeout".to_owned()));
+    let command = CommandBuilder::parse_args(args(&["--executable", "cargo"]))
+        .unwrap()
+        .timeout(Duration::from_secs(5))
+        .build()
+        .unwrap();
+    assert_eq!(command.timeout, Duration::from_secs(5));
+    let err = CommandBuilder::parse_args(args(&["--executable"])).unwrap_err();
+    assert_eq!(err, CommandBuilderError::MissingValue("--executable".to_owned()));
+
+    let err = CommandBuilder::parse_args(args(&["--help"])).unwrap_err();
+    assert_eq!(err, CommandBuilderError::Help);
+    assert_eq!(err.to_string(), CommandBuilder::HELP);
+    assert_eq!(
+        CommandBuilder::HELP,
+        "\
+Options:
+  --executable <VALUE>   The program to run. (required)
+  --arg <VALUE>...       An argument passed to the program.
+  --current-dir <VALUE>  Where the program runs.
+  --jobs <VALUE>         How many jobs run in parallel.
+  --verbose
+  --help                 Prints this help.
+",
+    );
+}
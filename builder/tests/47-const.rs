@@ -0,0 +1,49 @@
+// With #[builder(const)] the builder can be used in constants and statics:
+// builder(), the setters and build() are `const fn`, setters take the builder
+// by value, and build() returns the value itself, panicking if a required
+// field was not set.
+
+use derive_builder::Builder;
+
+#[derive(Builder, Debug, PartialEq)]
+#[builder(const)]
+pub struct Command {
+    executable: &'static str,
+    args: &'static [&'static str],
+    #[builder(setter(strip_option))]
+    current_dir: Option<&'static str>,
+    #[builder(default = "1 + retries")]
+    attempts: u32,
+    #[builder(default = "0")]
+    retries: u32,
+}
+
+static CMD: Command = Command::builder().executable("cargo").args(&["build"]).build();
+
+const COMMANDS: [Command; 2] = [
+    Command::builder().executable("ls").args(&[]).current_dir("/").build(),
+    Command::builder()
+        .executable("make")
+        .args(&["-j8"])
+        .retries(2)
+        .build(),
+];
+
+fn main() {
+    assert_eq!(
+        CMD,
+        Command {
+            executable: "cargo",
+            args: &["build"],
+            current_dir: None,
+            attempts: 1,
+            retries: 0,
+        }
+    );
+    assert_eq!(COMMANDS[0].current_dir, Some("/"));
+    assert_eq!(COMMANDS[1].attempts, 3);
+
+    // Outside of a constant, build() is still callable at runtime.
+    let command = Command::builder().executable("ls").args(&[]).build();
+    assert_eq!(command.executable, "ls");
+}
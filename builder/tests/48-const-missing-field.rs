@@ -0,0 +1,17 @@
+// A const build() panics if a required field was not set, which when
+// evaluating a constant is a compile error naming the field.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+#[builder(const)]
+pub struct Command {
+    executable: &'static str,
+    args: &'static [&'static str],
+}
+
+static CMD: Command = Command::builder().args(&["build"]).build();
+
+fn main() {
+    let _ = &CMD;
+}
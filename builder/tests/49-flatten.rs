@@ -0,0 +1,108 @@
+// #[builder(flatten)] on a field whose type also derives Builder puts the
+// nested builder's setters directly on the outer builder, as if its fields
+// were declared there, whatever pattern the outer builder uses. As with
+// sub_builder, a nested builder with pattern = "owned" needs an outer one
+// with pattern = "owned". build() builds the nested value along with the
+// outer one, and a missing field of the nested builder is reported as if it
+// were the outer builder's own.
+
+mod options {
+    use derive_builder::Builder;
+
+    /// Shared by every command that talks to the network.
+    #[derive(Builder, Debug, PartialEq)]
+    pub struct RetryPolicy {
+        pub max_retries: u32,
+        #[builder(default = "100")]
+        pub backoff_ms: u64,
+    }
+
+    #[derive(Builder, Debug, PartialEq)]
+    pub struct Output {
+        #[builder(setter(into))]
+        pub format: String,
+        #[builder(each = "column")]
+        pub columns: Vec<String>,
+    }
+
+    #[derive(Builder, Debug, PartialEq)]
+    #[builder(pattern = "owned")]
+    pub struct Progress {
+        pub interval_ms: u64,
+    }
+}
+
+use derive_builder::Builder;
+// As for sub_builder, the nested builder must be reachable through the same
+// path as the field's type, which a glob import covers.
+use options::*;
+
+#[derive(Builder, Debug)]
+pub struct Fetch {
+    url: String,
+    #[builder(flatten)]
+    retry: RetryPolicy,
+    #[builder(flatten)]
+    output: Option<Output>,
+}
+
+#[derive(Builder, Debug)]
+#[builder(pattern = "owned")]
+pub struct Upload {
+    path: String,
+    #[builder(flatten)]
+    retry: options::RetryPolicy,
+    #[builder(flatten)]
+    progress: Progress,
+}
+
+fn main() {
+    let mut builder = Fetch::builder();
+    builder
+        .url("https://example.com".to_owned())
+        .max_retries(3)
+        .format("json");
+    builder.column("id".to_owned()).column("name".to_owned());
+    let fetch = builder.build().unwrap();
+    assert_eq!(
+        fetch.retry,
+        RetryPolicy {
+            max_retries: 3,
+            backoff_ms: 100,
+        },
+    );
+    let output = fetch.output.unwrap();
+    assert_eq!(output.format, "json");
+    assert_eq!(output.columns, ["id", "name"]);
+
+    // An optional flattened field is only built if one of its setters was
+    // called.
+    let fetch = Fetch::builder()
+        .url("https://example.com".to_owned())
+        .max_retries(0)
+        .build()
+        .unwrap();
+    assert!(fetch.output.is_none());
+
+    let err = Fetch::builder()
+        .url("https://example.com".to_owned())
+        .build()
+        .unwrap_err();
+    assert_eq!(
+        err,
+        FetchBuilderError::Retry(options::RetryPolicyBuilderError::MissingMaxRetries),
+    );
+    assert_eq!(err.field_path().as_deref(), Some("max_retries"));
+    assert_eq!(err.to_string(), "missing required field `max_retries`");
+
+    let upload = Upload::builder()
+        .path("a.txt".to_owned())
+        .backoff_ms(5)
+        .max_retries(1)
+        .interval_ms(250)
+        .build()
+        .unwrap();
+    assert_eq!(upload.retry.backoff_ms, 5);
+    assert_eq!(upload.retry.max_retries, 1);
+    assert_eq!(upload.progress.interval_ms, 250);
+}
@@ -0,0 +1,20 @@
+// A setter forwarded from a flattened builder must not have the same name as
+// one of the outer builder's own setters.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+pub struct RetryPolicy {
+    max_retries: u32,
+    timeout_secs: u64,
+}
+
+#[derive(Builder)]
+pub struct Fetch {
+    url: String,
+    timeout_secs: u64,
+    #[builder(flatten)]
+    retry: RetryPolicy,
+}
+
+fn main() {}
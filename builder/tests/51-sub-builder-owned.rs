@@ -0,0 +1,20 @@
+// A builder that is not consumed by build() builds its nested builders
+// through a reference, which a nested builder with pattern = "owned" does not
+// allow. Nesting one is an error at the field rather than a borrow error
+// inside the generated build().
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+#[builder(pattern = "owned")]
+pub struct Listener {
+    address: String,
+}
+
+#[derive(Builder)]
+pub struct Server {
+    #[builder(sub_builder)]
+    listener: Listener,
+}
+
+fn main() {}
@@ -0,0 +1,34 @@
+// The nested builder and its error are found through hidden aliases named
+// after the field's type, so a struct whose builder is renamed with
+// builder(name = "...") can be nested like any other.
+
+use derive_builder::Builder;
+
+#[derive(Builder, Debug)]
+#[builder(name = "ListenerOptions")]
+pub struct Listener {
+    address: String,
+    #[builder(default = "80")]
+    port: u16,
+}
+
+#[derive(Builder, Debug)]
+pub struct Server {
+    #[builder(sub_builder)]
+    listener: Listener,
+}
+
+fn main() {
+    let mut builder = Server::builder();
+    let listener: &mut ListenerOptions = builder.listener();
+    listener.address("0.0.0.0".to_owned());
+    let server = builder.build().unwrap();
+    assert_eq!(server.listener.address, "0.0.0.0");
+    assert_eq!(server.listener.port, 80);
+
+    let err = Server::builder().build().unwrap_err();
+    assert!(matches!(
+        err,
+        ServerBuilderError::Listener(ListenerOptionsError::MissingAddress)
+    ));
+}
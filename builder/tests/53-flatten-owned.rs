@@ -0,0 +1,26 @@
+// A flattened builder is built the same way as a sub_builder one, so one with
+// pattern = "owned" cannot be flattened into a builder that build() does not
+// consume.
+
+mod options {
+    use derive_builder::Builder;
+
+    #[derive(Builder)]
+    #[builder(pattern = "owned")]
+    pub struct Progress {
+        #[builder(default)]
+        pub quiet: bool,
+    }
+}
+
+use derive_builder::Builder;
+use options::*;
+
+#[derive(Builder)]
+pub struct Download {
+    url: String,
+    #[builder(flatten)]
+    progress: Progress,
+}
+
+fn main() {}
@@ -0,0 +1,15 @@
+// With an env_prefix, every field is parsed with FromStr by from_env(). A
+// field whose type does not implement it is reported at the field, and has to
+// be left out with #[builder(env(skip))].
+
+use derive_builder::Builder;
+use std::time::Duration;
+
+#[derive(Builder)]
+#[builder(env_prefix = "APP_")]
+pub struct Client {
+    url: String,
+    timeout: Duration,
+}
+
+fn main() {}
@@ -0,0 +1,15 @@
+// Every field of a builder(cli) struct gets a flag whose value is parsed with
+// FromStr. A field whose type does not implement it is reported at the
+// field, and has to be left out with #[builder(cli(skip))].
+
+use derive_builder::Builder;
+use std::time::Duration;
+
+#[derive(Builder)]
+#[builder(cli)]
+pub struct Command {
+    executable: String,
+    timeout: Duration,
+}
+
+fn main() {}
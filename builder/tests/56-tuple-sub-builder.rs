@@ -0,0 +1,48 @@
+// A nested builder in a tuple struct. The error variant wrapping its error
+// cannot be named after the field, `_0`, so it is `Field0`, the way a missing
+// field is reported with `Missing1`.
+
+use derive_builder::Builder;
+
+#[derive(Builder, Debug, PartialEq)]
+pub struct Limits {
+    max_connections: u32,
+}
+
+#[derive(Builder, Debug, PartialEq)]
+pub struct Pool(#[builder(sub_builder)] Limits, u8);
+
+#[derive(Builder, Debug, PartialEq)]
+pub struct Cluster(#[builder(flatten)] Limits, String);
+
+fn main() {
+    let mut builder = Pool::builder();
+    builder._0().max_connections(16);
+    builder._1(2);
+    let pool = builder.build().unwrap();
+    assert_eq!(pool, Pool(Limits { max_connections: 16 }, 2));
+
+    let mut builder = Pool::builder();
+    builder._1(2);
+    let err = builder.build().unwrap_err();
+    assert_eq!(
+        err,
+        PoolBuilderError::Field0(LimitsBuilderError::MissingMaxConnections),
+    );
+    assert_eq!(err.field_path().as_deref(), Some("_0.max_connections"));
+
+    let mut builder = Pool::builder();
+    builder._0().max_connections(16);
+    assert_eq!(builder.build().unwrap_err(), PoolBuilderError::Missing1);
+
+    let mut builder = Cluster::builder();
+    builder.max_connections(4)._1("east".to_owned());
+    let cluster = builder.build().unwrap();
+    assert_eq!(cluster, Cluster(Limits { max_connections: 4 }, "east".to_owned()));
+
+    let err = Cluster::builder()._1("east".to_owned()).build().unwrap_err();
+    assert_eq!(
+        err,
+        ClusterBuilderError::Field0(LimitsBuilderError::MissingMaxConnections),
+    );
+}
@@ -0,0 +1,74 @@
+// A nested builder is built through hidden build functions rather than its
+// `build()`, so a nested struct with build_fn(name = "...") or
+// build_fn(error = "...") works, and its errors are wrapped as the builder's
+// own error whatever type its `build()` returns.
+
+use derive_builder::Builder;
+
+#[derive(Debug, PartialEq)]
+pub struct LimitsError(String);
+
+impl From<LimitsBuilderUninitializedFieldError> for LimitsError {
+    fn from(err: LimitsBuilderUninitializedFieldError) -> Self {
+        LimitsError(err.to_string())
+    }
+}
+
+#[derive(Builder, Debug, PartialEq)]
+#[builder(build_fn(name = "finish", error = "LimitsError"))]
+pub struct Limits {
+    #[builder(default = "16")]
+    max_connections: u32,
+    timeout_ms: u64,
+}
+
+#[derive(Builder, Debug, PartialEq)]
+#[builder(pattern = "owned", build_fn(name = "finish"))]
+pub struct Retry {
+    #[builder(default = "3")]
+    attempts: u32,
+}
+
+#[derive(Builder, Debug, PartialEq)]
+pub struct Server {
+    #[builder(sub_builder)]
+    limits: Limits,
+}
+
+#[derive(Builder, Debug, PartialEq)]
+#[builder(pattern = "owned")]
+pub struct Client {
+    #[builder(sub_builder)]
+    limits: Limits,
+    #[builder(flatten)]
+    retry: Retry,
+}
+
+fn main() {
+    let mut builder = Server::builder();
+    builder.limits().timeout_ms(500);
+    let server = builder.build().unwrap();
+    assert_eq!(server.limits.max_connections, 16);
+    assert_eq!(server.limits.timeout_ms, 500);
+
+    let err = Server::builder().build().unwrap_err();
+    assert_eq!(
+        err,
+        ServerBuilderError::Limits(LimitsBuilderError::MissingTimeoutMs),
+    );
+
+    let mut builder = Client::builder();
+    builder.limits().timeout_ms(100);
+    let client = builder.attempts(5).build().unwrap();
+    assert_eq!(client.limits.timeout_ms, 100);
+    assert_eq!(client.retry.attempts, 5);
+
+    let err = Client::builder().build().unwrap_err();
+    assert_eq!(
+        err,
+        ClientBuilderError::Limits(LimitsBuilderError::MissingTimeoutMs),
+    );
+
+    let err = Limits::builder().finish().unwrap_err();
+    assert_eq!(err, LimitsError("missing required field `timeout_ms`".to_owned()));
+}
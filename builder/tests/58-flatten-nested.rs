@@ -0,0 +1,80 @@
+// A flattened struct may itself have flatten and sub_builder fields. Their
+// setters and accessors reach the outer builder as well, whatever the
+// patterns of the builders in between. As always, the nested builders of an
+// immutable builder need to be `Clone`.
+
+use derive_builder::Builder;
+
+#[derive(Builder, Debug, PartialEq)]
+#[builder(derive(Clone))]
+pub struct Backoff {
+    ms: u64,
+    #[builder(default = "2")]
+    factor: u32,
+}
+
+#[derive(Builder, Debug, PartialEq)]
+#[builder(derive(Clone))]
+pub struct Limits {
+    #[builder(default = "8")]
+    max_in_flight: u32,
+}
+
+#[derive(Builder, Debug, PartialEq)]
+#[builder(pattern = "immutable")]
+pub struct Retry {
+    attempts: u32,
+    #[builder(flatten)]
+    backoff: Backoff,
+    #[builder(sub_builder)]
+    limits: Limits,
+}
+
+#[derive(Builder, Debug)]
+pub struct Command {
+    program: String,
+    #[builder(flatten)]
+    retry: Retry,
+}
+
+#[derive(Builder, Debug)]
+#[builder(pattern = "owned")]
+pub struct Upload {
+    #[builder(flatten)]
+    retry: Retry,
+}
+
+fn main() {
+    let mut builder = Command::builder();
+    builder
+        .program("curl".to_owned())
+        .attempts(3)
+        .ms(250)
+        .factor(3);
+    builder.limits().max_in_flight(4);
+    let command = builder.build().unwrap();
+    assert_eq!(command.program, "curl");
+    assert_eq!(
+        command.retry,
+        Retry {
+            attempts: 3,
+            backoff: Backoff { ms: 250, factor: 3 },
+            limits: Limits { max_in_flight: 4 },
+        },
+    );
+
+    let err = Command::builder()
+        .program("curl".to_owned())
+        .attempts(3)
+        .build()
+        .unwrap_err();
+    assert_eq!(
+        err,
+        CommandBuilderError::Retry(RetryBuilderError::Backoff(BackoffBuilderError::MissingMs)),
+    );
+    assert_eq!(err.field_path().as_deref(), Some("ms"));
+
+    let upload = Upload::builder().attempts(1).ms(10).build().unwrap();
+    assert_eq!(upload.retry.backoff, Backoff { ms: 10, factor: 2 });
+    assert_eq!(upload.retry.limits, Limits { max_in_flight: 8 });
+}
@@ -1,13 +1,64 @@
 #[test]
 fn tests() {
     let t = trybuild::TestCases::new();
-    //t.pass("tests/01-parse.rs");
-    //t.pass("tests/02-create-builder.rs");
-    //t.pass("tests/03-call-setters.rs");
-    //t.pass("tests/04-call-build.rs");
-    //t.pass("tests/05-method-chaining.rs");
-    //t.pass("tests/06-optional-field.rs");
-    //t.pass("tests/07-repeated-field.rs");
-    //t.compile_fail("tests/08-unrecognized-attribute.rs");
-    //t.pass("tests/09-redefined-prelude-types.rs");
+    t.pass("tests/01-parse.rs");
+    t.pass("tests/02-create-builder.rs");
+    t.pass("tests/03-call-setters.rs");
+    t.pass("tests/04-call-build.rs");
+    t.pass("tests/05-method-chaining.rs");
+    t.pass("tests/06-optional-field.rs");
+    t.pass("tests/07-repeated-field.rs");
+    t.compile_fail("tests/08-unrecognized-attribute.rs");
+    t.pass("tests/09-redefined-prelude-types.rs");
+    t.pass("tests/10-typed-error.rs");
+    t.pass("tests/11-typestate.rs");
+    t.compile_fail("tests/12-typestate-missing-field.rs");
+    t.pass("tests/13-default.rs");
+    t.pass("tests/14-validate.rs");
+    t.pass("tests/15-generic-type-params.rs");
+    t.pass("tests/16-lifetime-params.rs");
+    t.pass("tests/17-const-generics.rs");
+    t.pass("tests/18-where-clause.rs");
+    t.pass("tests/19-setter-into.rs");
+    t.pass("tests/20-setter-options.rs");
+    t.compile_fail("tests/21-setter-skip-required.rs");
+    t.pass("tests/22-owned-pattern.rs");
+    t.pass("tests/23-immutable-pattern.rs");
+    t.pass("tests/24-each-collections.rs");
+    t.pass("tests/25-sub-builder.rs");
+    t.pass("tests/26-to-builder.rs");
+    t.pass("tests/27-merge.rs");
+    t.compile_fail("tests/28-merge-append-requires-each.rs");
+    if cfg!(feature = "serde") {
+        t.pass("tests/29-serde.rs");
+    }
+    t.pass("tests/30-env.rs");
+    t.compile_fail("tests/31-env-unsupported-field.rs");
+    t.pass("tests/32-enum.rs");
+    t.compile_fail("tests/33-enum-tuple-variant.rs");
+    t.pass("tests/34-tuple-struct.rs");
+    t.pass("tests/35-positional.rs");
+    t.compile_fail("tests/36-unit-struct.rs");
+    t.compile_fail("tests/37-union.rs");
+    t.pass("tests/38-try-setter.rs");
+    t.compile_fail("tests/39-try-setter-each.rs");
+    t.pass("tests/40-builder-customization.rs");
+    t.pass("tests/41-introspection.rs");
+    t.pass("tests/42-no-std.rs");
+    t.pass("tests/43-build-fn.rs");
+    t.pass("tests/44-default-with-computed.rs");
+    t.compile_fail("tests/45-default-cycle.rs");
+    t.pass("tests/46-cli.rs");
+    t.pass("tests/47-const.rs");
+    t.compile_fail("tests/48-const-missing-field.rs");
+    t.pass("tests/49-flatten.rs");
+    t.compile_fail("tests/50-flatten-collision.rs");
+    t.compile_fail("tests/51-sub-builder-owned.rs");
+    t.pass("tests/52-sub-builder-renamed.rs");
+    t.compile_fail("tests/53-flatten-owned.rs");
+    t.compile_fail("tests/54-env-not-from-str.rs");
+    t.compile_fail("tests/55-cli-not-from-str.rs");
+    t.pass("tests/56-tuple-sub-builder.rs");
+    t.pass("tests/57-sub-builder-build-fn.rs");
+    t.pass("tests/58-flatten-nested.rs");
 }
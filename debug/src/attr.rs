@@ -0,0 +1,88 @@
+use syn::punctuated::Punctuated;
+use syn::{Attribute, Error, Expr, Lit, LitStr, Meta, Result, Token, WherePredicate};
+
+const CONTAINER_EXPECTED: &str = "expected `debug(bound = \"...\")`";
+const FIELD_EXPECTED: &str = "expected `debug = \"...\"` or `debug(bound = \"...\")`";
+
+pub type Bounds = Punctuated<WherePredicate, Token![,]>;
+
+/// Options parsed from the `#[debug(...)]` attributes on the struct or enum.
+#[derive(Default)]
+pub struct ContainerAttrs {
+    /// Bounds for the impl, replacing all of the inferred ones.
+    pub bound: Option<Bounds>,
+}
+
+/// Options parsed from the `#[debug...]` attributes on a single field.
+#[derive(Default)]
+pub struct FieldAttrs {
+    /// `#[debug = "..."]`: the format string the field is written with.
+    pub format: Option<LitStr>,
+    /// Bounds replacing those inferred from this field's type.
+    pub bound: Option<Bounds>,
+}
+
+impl ContainerAttrs {
+    pub fn parse(attrs: &[Attribute]) -> Result<Self> {
+        let mut out = ContainerAttrs::default();
+        for attr in debug_attrs(attrs) {
+            if !matches!(attr.meta, Meta::List(_)) {
+                return Err(Error::new_spanned(&attr.meta, CONTAINER_EXPECTED));
+            }
+            out.bound = Some(parse_bound(attr, CONTAINER_EXPECTED)?);
+        }
+        Ok(out)
+    }
+}
+
+impl FieldAttrs {
+    pub fn parse(attrs: &[Attribute]) -> Result<Self> {
+        let mut out = FieldAttrs::default();
+        for attr in debug_attrs(attrs) {
+            match &attr.meta {
+                Meta::NameValue(meta) => match &meta.value {
+                    Expr::Lit(expr) => match &expr.lit {
+                        Lit::Str(lit) => out.format = Some(lit.clone()),
+                        _ => return Err(Error::new_spanned(&expr.lit, "expected a format string")),
+                    },
+                    value => return Err(Error::new_spanned(value, "expected a format string")),
+                },
+                Meta::List(_) => out.bound = Some(parse_bound(attr, FIELD_EXPECTED)?),
+                Meta::Path(_) => return Err(Error::new_spanned(&attr.meta, FIELD_EXPECTED)),
+            }
+        }
+        Ok(out)
+    }
+}
+
+/// Rejects `#[debug...]` on an enum variant, where there is nothing to
+/// configure.
+pub fn check_variant(attrs: &[Attribute]) -> Result<()> {
+    match debug_attrs(attrs).next() {
+        Some(attr) => Err(Error::new_spanned(
+            attr,
+            "`#[debug]` is not supported on enum variants; put it on the enum or on the fields",
+        )),
+        None => Ok(()),
+    }
+}
+
+fn debug_attrs(attrs: &[Attribute]) -> impl Iterator<Item = &Attribute> {
+    attrs.iter().filter(|attr| attr.path().is_ident("debug"))
+}
+
+/// Parses `debug(bound = "...")`, where the string holds where-predicates
+/// such as `T::Value: Debug`. An empty string means no bounds at all.
+fn parse_bound(attr: &Attribute, expected: &str) -> Result<Bounds> {
+    let mut bound = None;
+    attr.parse_nested_meta(|meta| {
+        if meta.path.is_ident("bound") {
+            let lit: LitStr = meta.value()?.parse()?;
+            bound = Some(lit.parse_with(Bounds::parse_terminated)?);
+            Ok(())
+        } else {
+            Err(Error::new_spanned(&meta.path, expected))
+        }
+    })?;
+    bound.ok_or_else(|| Error::new_spanned(attr, expected))
+}
@@ -0,0 +1,91 @@
+//! The where clause of the generated impl. Unless the bounds are given with
+//! `debug(bound = "...")`, they are inferred from the fields of every variant
+//! together: `T: Debug` for each type parameter used other than as
+//! `PhantomData<T>`, and `T::Value: Debug` for each associated type used.
+
+use crate::attr::Bounds;
+use crate::expand::DebugField;
+use quote::ToTokens;
+use syn::visit::{self, Visit};
+use syn::{parse_quote, Generics, Ident, Type, TypePath, WherePredicate};
+
+/// `generics` with the bounds for the fields added to its where clause.
+pub fn with_bounds(
+    generics: &Generics,
+    bound: Option<&Bounds>,
+    fields: &[&DebugField],
+) -> Generics {
+    let mut generics = generics.clone();
+    let predicates: Vec<WherePredicate> = match bound {
+        Some(bound) => bound.iter().cloned().collect(),
+        None => infer(&generics, fields),
+    };
+    generics.make_where_clause().predicates.extend(predicates);
+    generics
+}
+
+fn infer(generics: &Generics, fields: &[&DebugField]) -> Vec<WherePredicate> {
+    let mut uses = Uses {
+        params: generics.type_params().map(|param| &param.ident).collect(),
+        used: Vec::new(),
+        assoc: Vec::new(),
+    };
+    let mut explicit = Vec::new();
+    for field in fields {
+        match &field.attrs.bound {
+            Some(bound) => explicit.extend(bound.iter().cloned()),
+            None if is_phantom(&field.field.ty) => {}
+            None => uses.visit_type(&field.field.ty),
+        }
+    }
+
+    // Parameters keep their declaration order.
+    let params = generics
+        .type_params()
+        .map(|param| &param.ident)
+        .filter(|ident| uses.used.contains(ident));
+    let params = params.map(|ident| parse_quote!(#ident: ::core::fmt::Debug));
+    let assoc = (uses.assoc.iter()).map(|path| parse_quote!(#path: ::core::fmt::Debug));
+    params.chain(assoc).chain(explicit).collect()
+}
+
+/// `PhantomData<T>` is `Debug` whether or not `T` is.
+fn is_phantom(ty: &Type) -> bool {
+    let Type::Path(path) = ty else {
+        return false;
+    };
+    path.qself.is_none()
+        && path
+            .path
+            .segments
+            .last()
+            .is_some_and(|segment| segment.ident == "PhantomData")
+}
+
+/// The type parameters a type mentions, and the associated types of type
+/// parameters, such as `T::Value`, that it mentions.
+struct Uses<'a> {
+    params: Vec<&'a Ident>,
+    used: Vec<Ident>,
+    assoc: Vec<TypePath>,
+}
+
+impl<'ast> Visit<'ast> for Uses<'_> {
+    fn visit_type_path(&mut self, ty: &'ast TypePath) {
+        let first = ty.path.segments.first().map(|segment| &segment.ident);
+        if let Some(param) = first.filter(|ident| ty.qself.is_none() && self.params.contains(ident))
+        {
+            if ty.path.segments.len() > 1 {
+                let tokens = ty.to_token_stream().to_string();
+                if !(self.assoc.iter()).any(|assoc| assoc.to_token_stream().to_string() == tokens) {
+                    self.assoc.push(ty.clone());
+                }
+                return;
+            }
+            if !self.used.contains(param) {
+                self.used.push(param.clone());
+            }
+        }
+        visit::visit_type_path(self, ty);
+    }
+}
@@ -0,0 +1,154 @@
+use crate::attr::{self, ContainerAttrs, FieldAttrs};
+use crate::bound;
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::ext::IdentExt;
+use syn::{Data, DeriveInput, Error, Fields, Ident, Index, Member, Result};
+
+/// A field of the struct or of one of the variants, as seen by the impl.
+pub struct DebugField<'a> {
+    pub field: &'a syn::Field,
+    pub attrs: FieldAttrs,
+    member: Member,
+    /// The local the field is bound to by reference in the `match`.
+    binding: Ident,
+}
+
+impl DebugField<'_> {
+    /// The name written before the field's value, for named fields.
+    fn name(&self) -> Option<String> {
+        let ident = self.field.ident.as_ref()?;
+        Some(ident.unraw().to_string())
+    }
+
+    /// The value handed to the `Formatter`: the field, or the field written
+    /// with its `#[debug = "..."]` format string.
+    fn value(&self) -> TokenStream {
+        let binding = &self.binding;
+        match &self.attrs.format {
+            Some(format) => quote!(&::core::format_args!(#format, #binding)),
+            None => quote!(#binding),
+        }
+    }
+}
+
+/// The struct, or one variant of the enum, with the fields it is written
+/// with.
+struct Shape<'a> {
+    /// The path matched on, `Self` or `Self::Variant`.
+    path: TokenStream,
+    /// The name written out, without the enum's name as in `#[derive(Debug)]`.
+    name: &'a Ident,
+    style: Style,
+    fields: Vec<DebugField<'a>>,
+}
+
+enum Style {
+    Named,
+    Tuple,
+    Unit,
+}
+
+pub fn derive(input: &DeriveInput) -> Result<TokenStream> {
+    let container = ContainerAttrs::parse(&input.attrs)?;
+    let shapes = match &input.data {
+        Data::Struct(data) => vec![Shape::new(quote!(Self), &input.ident, &data.fields)?],
+        Data::Enum(data) => data
+            .variants
+            .iter()
+            .map(|variant| {
+                attr::check_variant(&variant.attrs)?;
+                let ident = &variant.ident;
+                Shape::new(quote!(Self::#ident), ident, &variant.fields)
+            })
+            .collect::<Result<_>>()?,
+        Data::Union(data) => {
+            return Err(Error::new_spanned(
+                data.union_token,
+                "`derive(CustomDebug)` does not support unions",
+            ))
+        }
+    };
+
+    let fields: Vec<&DebugField> = shapes.iter().flat_map(|shape| &shape.fields).collect();
+    let generics = bound::with_bounds(&input.generics, container.bound.as_ref(), &fields);
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let ident = &input.ident;
+
+    let arms = shapes.iter().map(Shape::arm);
+    // A reference to an enum without variants is not known to be empty, but
+    // the enum itself is.
+    let body = if shapes.is_empty() {
+        quote!(match *self {})
+    } else {
+        quote!(match self { #(#arms)* })
+    };
+
+    Ok(quote! {
+        impl #impl_generics ::core::fmt::Debug for #ident #ty_generics #where_clause {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                #body
+            }
+        }
+    })
+}
+
+impl<'a> Shape<'a> {
+    fn new(path: TokenStream, name: &'a Ident, fields: &'a Fields) -> Result<Self> {
+        let style = match fields {
+            Fields::Named(_) => Style::Named,
+            Fields::Unnamed(_) => Style::Tuple,
+            Fields::Unit => Style::Unit,
+        };
+        let fields = fields
+            .iter()
+            .enumerate()
+            .map(|(i, field)| {
+                let member = match &field.ident {
+                    Some(ident) => Member::Named(ident.clone()),
+                    None => Member::Unnamed(Index::from(i)),
+                };
+                Ok(DebugField {
+                    field,
+                    attrs: FieldAttrs::parse(&field.attrs)?,
+                    member,
+                    binding: format_ident!("__self_{}", i),
+                })
+            })
+            .collect::<Result<_>>()?;
+        Ok(Shape {
+            path,
+            name,
+            style,
+            fields,
+        })
+    }
+
+    /// Writes the shape the way `#[derive(Debug)]` would.
+    fn arm(&self) -> TokenStream {
+        let path = &self.path;
+        let name = self.name.unraw().to_string();
+        let members = self.fields.iter().map(|field| &field.member);
+        let bindings = self.fields.iter().map(|field| &field.binding);
+        let values = self.fields.iter().map(DebugField::value);
+        let write = match self.style {
+            Style::Named => {
+                let names = self.fields.iter().map(DebugField::name);
+                quote! {
+                    ::core::fmt::Formatter::debug_struct(f, #name)
+                        #(.field(#names, &#values))*
+                        .finish()
+                }
+            }
+            Style::Tuple => quote! {
+                ::core::fmt::Formatter::debug_tuple(f, #name)
+                    #(.field(&#values))*
+                    .finish()
+            },
+            Style::Unit => quote!(::core::fmt::Formatter::write_str(f, #name)),
+        };
+        quote! {
+            #path { #(#members: #bindings,)* } => #write,
+        }
+    }
+}
@@ -0,0 +1,73 @@
+// Enums are written the way #[derive(Debug)] writes them: the variant's name,
+// followed by its fields as a struct or a tuple, if it has any. The field
+// attribute #[debug = "..."] works on the fields of variants too.
+
+use derive_debug::CustomDebug;
+
+#[derive(CustomDebug)]
+pub enum Error {
+    Io {
+        path: &'static str,
+        #[debug = "0o{:o}"]
+        mode: u32,
+    },
+    Parse(&'static str, #[debug = "line {}"] usize),
+    Eof,
+}
+
+// The same shapes written by std's derive and by this one.
+#[derive(Debug)]
+pub enum StdShape {
+    Named { f: u8, r#type: Option<&'static str> },
+    Tuple(u8, &'static str),
+    EmptyTuple(),
+    EmptyNamed {},
+    Unit,
+}
+
+#[derive(CustomDebug)]
+pub enum Shape {
+    Named { f: u8, r#type: Option<&'static str> },
+    Tuple(u8, &'static str),
+    EmptyTuple(),
+    EmptyNamed {},
+    Unit,
+}
+
+#[derive(CustomDebug)]
+pub enum Never {}
+
+fn main() {
+    let io = Error::Io {
+        path: "/etc",
+        mode: 0o755,
+    };
+    assert_eq!(format!("{:?}", io), r#"Io { path: "/etc", mode: 0o755 }"#);
+    assert_eq!(format!("{:?}", Error::Parse("x", 3)), r#"Parse("x", line 3)"#);
+    assert_eq!(format!("{:?}", Error::Eof), "Eof");
+    assert_eq!(
+        format!("{:#?}", Error::Parse("x", 3)),
+        "Parse(\n    \"x\",\n    line 3,\n)",
+    );
+
+    let shapes = [
+        (
+            Shape::Named {
+                f: 1,
+                r#type: Some("a"),
+            },
+            StdShape::Named {
+                f: 1,
+                r#type: Some("a"),
+            },
+        ),
+        (Shape::Tuple(2, "b"), StdShape::Tuple(2, "b")),
+        (Shape::EmptyTuple(), StdShape::EmptyTuple()),
+        (Shape::EmptyNamed {}, StdShape::EmptyNamed {}),
+        (Shape::Unit, StdShape::Unit),
+    ];
+    for (shape, std) in &shapes {
+        assert_eq!(format!("{:?}", shape), format!("{:?}", std));
+        assert_eq!(format!("{:#?}", shape), format!("{:#?}", std));
+    }
+}
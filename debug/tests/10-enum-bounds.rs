@@ -0,0 +1,43 @@
+// Bounds are inferred from the fields of all variants together: a type
+// parameter used by any variant other than through PhantomData needs to be
+// Debug, as does an associated type used by any variant. #[debug(bound =
+// "...")] on the enum replaces the inferred bounds as it does on a struct.
+
+use derive_debug::CustomDebug;
+use std::fmt::Debug;
+use std::marker::PhantomData;
+
+pub trait Trait {
+    type Value;
+}
+
+#[derive(CustomDebug)]
+pub enum Message<T: Trait, U, M> {
+    Value(T::Value),
+    Other { other: U },
+    Marker(PhantomData<M>),
+}
+
+#[derive(CustomDebug)]
+#[debug(bound = "T::Value: Debug")]
+pub enum Wrapper<T: Trait> {
+    Message(Message<T, u8, T>),
+    Empty,
+}
+
+fn assert_debug<F: Debug>() {}
+
+fn main() {
+    // Does not implement Debug, but its associated type does.
+    struct Id;
+
+    impl Trait for Id {
+        type Value = u8;
+    }
+
+    assert_debug::<Message<Id, String, Id>>();
+    assert_debug::<Wrapper<Id>>();
+
+    let message: Message<Id, (), Id> = Message::Value(7);
+    assert_eq!(format!("{:?}", message), "Value(7)");
+}